@@ -20,6 +20,9 @@ impl<R: Read> BytePairs<R> {
             //
             // We use a Fuse so that as soon as we see a None from the Bytes iterator,
             // we consider ourself to be done.
+            //
+            // Callers are expected to hand us a buffered (or in-memory) reader.
+            #[allow(clippy::unbuffered_bytes)]
             bytes: reader.bytes().fuse(),
         }
     }
@@ -0,0 +1,94 @@
+use super::Cipher;
+use std::any::type_name;
+use std::fmt::{self, Debug, Display, Formatter, Write};
+use std::str::from_utf8;
+
+const LINE_WIDTH: usize = 64;
+
+/**
+ * Enciphered displays bytes as the ciphertext a Cipher would produce for them, writing each
+ * encrypted char pair straight into the formatter.
+ *
+ * The alternate form (`{:#}`) breaks the output into lines of 64 characters. A width
+ * (`{:#40}`) sets the line length instead.
+ */
+pub struct Enciphered<'a, C, const N: usize> {
+    cipher: &'a C,
+    bytes: &'a [u8],
+}
+
+impl<'a, C: Cipher<N>, const N: usize> Enciphered<'a, C, N> {
+    pub fn new(cipher: &'a C, bytes: &'a [u8]) -> Self {
+        Enciphered { cipher, bytes }
+    }
+}
+
+impl<C: Cipher<N>, const N: usize> Display for Enciphered<'_, C, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let line_width = f.width().unwrap_or(LINE_WIDTH).max(1);
+        for (i, pair) in self.bytes.chunks(2).enumerate() {
+            if f.alternate() && i > 0 && i % line_width == 0 {
+                f.write_char('\n')?;
+            }
+            let encrypted = self
+                .cipher
+                .encrypt_char_pair((pair[0], pair.get(1).copied()));
+            f.write_str(from_utf8(&encrypted).map_err(|_| fmt::Error)?)?;
+        }
+        Ok(())
+    }
+}
+
+impl<C: Cipher<N>, const N: usize> Debug for Enciphered<'_, C, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Enciphered")
+            .field("cipher", &cipher_name::<C>())
+            .field("len", &self.bytes.len())
+            .finish()
+    }
+}
+
+fn cipher_name<C>() -> &'static str {
+    let name = type_name::<C>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Enciphered;
+    use crate::cipher::{Cipher, Extended, Standard};
+
+    #[test]
+    fn display_matches_encrypt() {
+        let bytes = "hello there".as_bytes();
+        let expected = Standard.encrypt_to_string(bytes);
+        assert_eq!(expected, format!("{}", Enciphered::new(&Standard, bytes)));
+        assert_eq!(expected, Enciphered::new(&Standard, bytes).to_string());
+    }
+
+    #[test]
+    fn alternate_wraps_lines() {
+        let bytes = "a longer string than most of the others".as_bytes();
+        let wrapped = format!("{:#8}", Enciphered::new(&Extended, bytes));
+        let lines: Vec<&str> = wrapped.lines().collect();
+        assert_eq!(3, lines.len());
+        assert_eq!(8, lines[0].chars().count());
+        assert_eq!(8, lines[1].chars().count());
+        assert_eq!(4, lines[2].chars().count());
+        assert_eq!(Extended.encrypt_to_string(bytes), lines.concat());
+    }
+
+    #[test]
+    fn alternate_defaults_to_64_chars() {
+        let bytes = [b'x'; 200];
+        let wrapped = format!("{:#}", Enciphered::new(&Standard, &bytes));
+        let widths: Vec<usize> = wrapped.lines().map(|line| line.chars().count()).collect();
+        assert_eq!(vec![64, 36], widths);
+    }
+
+    #[test]
+    fn debug_shows_cipher_name() {
+        let debug = format!("{:?}", Enciphered::new(&Standard, "secret".as_bytes()));
+        assert_eq!("Enciphered { cipher: \"Standard\", len: 6 }", debug);
+    }
+}
@@ -1,50 +1,69 @@
+use super::layout::{Bit, Field, Layout, Overlong, Source};
 use super::{BytePair, Cipher};
+use crate::info::{CipherInfo, Describe, Expansion};
 
-const SIG_BIT_MASK: u8 = 0b0100_0000;
-const LOWER_BITS_MASK: u8 = 0b0011_1111;
-const SINGLE_CHAR_MASK: u8 = 0b0000_0100;
-const CHAR_WIDTH_MASK: u8 = 0b1111_0000;
-const CONINUATION_MASK: u8 = 0b1000_0000;
-// Needed to ensure that our generated code point is in the acceptable range for a
-// 4-byte unicode character.
-const OVERLONG_AVOIDANCE_MASK: u8 = 0b0001_0000;
+/**
+ * Like Standard's layout, shifted along a byte: the significant bits and the single byte
+ * flag share the first continuation byte, leaving the leading byte as just the width marker.
+ * The fifth bit of that continuation byte is always set, to keep the code point in the range
+ * that needs 4 bytes.
+ */
+pub const LAYOUT: Layout<4> = Layout {
+    prefix: [0b1111_0000, 0b1000_0000, 0b1000_0000, 0b1000_0000],
+    fields: &[
+        Field {
+            source: Source::First,
+            low: 6,
+            len: 1,
+            byte: 1,
+            at: 1,
+        },
+        Field {
+            source: Source::First,
+            low: 0,
+            len: 6,
+            byte: 2,
+            at: 0,
+        },
+        Field {
+            source: Source::Second,
+            low: 6,
+            len: 1,
+            byte: 1,
+            at: 0,
+        },
+        Field {
+            source: Source::Second,
+            low: 0,
+            len: 6,
+            byte: 3,
+            at: 0,
+        },
+    ],
+    single: Bit { byte: 1, bit: 2 },
+    overlong: Overlong::Always(Bit { byte: 1, bit: 4 }),
+};
 
 pub struct Extended;
 
+impl Describe for Extended {
+    const INFO: CipherInfo = CipherInfo {
+        name: "extended",
+        id: 2,
+        description: "7-bit byte pairs packed into 4-byte utf-8 characters",
+        expansion: Expansion::Fixed(2.0),
+        // Full pairs, then lone trailing bytes.
+        output_ranges: &[0x10000..=0x13FFF, 0x14000..=0x14FC0, 0x16000..=0x16FC0],
+        binary_safe: false,
+    };
+}
+
 impl Cipher<4> for Extended {
     fn encrypt_char_pair(&self, pair: BytePair) -> [u8; 4] {
-        let mut encrypted_char = [0, 0, 0, 0];
-        let c0 = pair.0;
-        let sig_0 = c0 & SIG_BIT_MASK;
-        let low_0 = c0 & LOWER_BITS_MASK;
-        encrypted_char[0] = CHAR_WIDTH_MASK;
-        encrypted_char[1] = CONINUATION_MASK | OVERLONG_AVOIDANCE_MASK | (sig_0 >> 5);
-        encrypted_char[2] = CONINUATION_MASK | low_0;
-
-        if let Some(c1) = pair.1 {
-            let sig_1 = c1 & SIG_BIT_MASK;
-            let low_1 = c1 & LOWER_BITS_MASK;
-            encrypted_char[1] = encrypted_char[1] | (sig_1 >> 6);
-            encrypted_char[3] = CONINUATION_MASK | low_1;
-        } else {
-            encrypted_char[1] = encrypted_char[1] | SINGLE_CHAR_MASK;
-            encrypted_char[3] = CONINUATION_MASK;
-        }
-        return encrypted_char;
+        LAYOUT.encrypt_char_pair(pair)
     }
 
     fn decrypt_char_pair(&self, encrypted: [u8; 4]) -> BytePair {
-        let c0_sig_bit = (encrypted[1] & 2) << 5;
-        let c0_lower = encrypted[2] & LOWER_BITS_MASK;
-        let c0 = c0_sig_bit | c0_lower;
-
-        if encrypted[1] & SINGLE_CHAR_MASK != 0 {
-            return (c0, None);
-        }
-
-        let c1_sig_bit = (encrypted[1] & 1) << 6;
-        let c1_lower = encrypted[3] & LOWER_BITS_MASK;
-        let c1 = c1_sig_bit | c1_lower;
-        return (c0, Some(c1));
+        LAYOUT.decrypt_char_pair(encrypted)
     }
 }
@@ -0,0 +1,204 @@
+use super::{BytePair, Cipher};
+use anyhow::{Error, Result};
+use std::str::from_utf8;
+
+/// Which byte of the pair a Field takes its bits from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Source {
+    First,
+    Second,
+}
+
+/// A single bit of the encrypted char.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bit {
+    pub byte: usize,
+    pub bit: u8,
+}
+
+/// A run of `len` bits of a plaintext byte, from bit `low` up, placed in `byte` of the
+/// encrypted char from bit `at` up.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Field {
+    pub source: Source,
+    pub low: u8,
+    pub len: u8,
+    pub byte: usize,
+    pub at: u8,
+}
+
+/// How a layout keeps its chars from being overlong, too small to need N bytes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Overlong {
+    /// The fields can never leave the char too small.
+    Never,
+    /// The bit is always set.
+    Always(Bit),
+    /// The bit is only set when the char would otherwise be invalid utf-8.
+    WhenInvalid(Bit),
+}
+
+/**
+ * Layout declares how a Cipher<N> lays a byte pair out across the bytes of an N byte utf-8
+ * char: the fixed prefix bits of each byte, which includes the width marker on the first
+ * one, where each run of plaintext bits goes, the flag marking a lone trailing byte and
+ * the bit that keeps chars from being overlong. A Layout is itself a Cipher<N>.
+ *
+ * Between them the fields have to carry the low 7 bits of both bytes. Nothing stops a layout
+ * being written that produces invalid utf-8 or leaves some of those bits out, so ones that
+ * don't come from this crate should be checked with validate before they're used.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Layout<const N: usize> {
+    pub prefix: [u8; N],
+    pub fields: &'static [Field],
+    pub single: Bit,
+    pub overlong: Overlong,
+}
+
+impl<const N: usize> Layout<N> {
+    /// Checks that the layout is well formed, that its fields carry all 7 bits of each byte,
+    /// and that every pair it could be given comes out as valid utf-8 that decrypts back.
+    pub fn validate(&self) -> Result<()> {
+        if !(2..=4).contains(&N) {
+            return Err(Error::msg(format!(
+                "layouts are for 2 to 4 byte chars, not {}",
+                N
+            )));
+        }
+        let width_marker = !(0xFF >> N);
+        if self.prefix[0] & (width_marker | 0x80 >> N) != width_marker {
+            return Err(Error::msg(format!(
+                "first prefix byte {:08b} doesn't mark a {} byte char",
+                self.prefix[0], N
+            )));
+        }
+        if let Some(byte) = self.prefix[1..].iter().find(|byte| *byte & 0xC0 != 0x80) {
+            return Err(Error::msg(format!(
+                "prefix byte {:08b} isn't a continuation byte",
+                byte
+            )));
+        }
+
+        // Bits that are already spoken for, starting with the utf-8 markers and the prefix.
+        let mut used = self.prefix;
+        used[0] |= width_marker | 0x80 >> N;
+        for byte in &mut used[1..] {
+            *byte |= 0xC0;
+        }
+        let mut claim = |byte: usize, mask: u8, what: &str| -> Result<()> {
+            if byte >= N || used[byte] & mask != 0 {
+                return Err(Error::msg(format!(
+                    "{} overlaps other bits of byte {}, or is out of range",
+                    what, byte
+                )));
+            }
+            used[byte] |= mask;
+            Ok(())
+        };
+        let mut sources = [0u8; 2];
+        for field in self.fields {
+            if !(1..=8).contains(&field.len)
+                || field.low > 8 - field.len
+                || field.at > 8 - field.len
+            {
+                return Err(Error::msg(format!("field {:?} is out of range", field)));
+            }
+            let mask = mask(field.len);
+            claim(field.byte, mask << field.at, &format!("field {:?}", field))?;
+            let source = &mut sources[field.source as usize];
+            if *source & mask << field.low != 0 {
+                return Err(Error::msg(format!(
+                    "field {:?} takes bits another field already has",
+                    field
+                )));
+            }
+            *source |= mask << field.low;
+        }
+        let mut flags = vec![(self.single, "the single byte flag")];
+        if let Overlong::Always(overlong) | Overlong::WhenInvalid(overlong) = self.overlong {
+            flags.push((overlong, "the overlong bit"));
+        }
+        for (flag, what) in flags {
+            if flag.bit > 7 {
+                return Err(Error::msg(format!("{} is out of range", what)));
+            }
+            claim(flag.byte, bit(flag), what)?;
+        }
+        if sources != [0x7F, 0x7F] {
+            return Err(Error::msg(format!(
+                "the fields carry bits {:08b} of the first byte and {:08b} of the second, \
+                 rather than all 7 of each",
+                sources[0], sources[1]
+            )));
+        }
+
+        for c0 in 0..=u8::MAX {
+            let seconds = (0..=u8::MAX).map(Some).chain([None]);
+            for pair in seconds.map(|c1| (c0, c1)) {
+                let encrypted = self.encrypt_char_pair(pair);
+                if from_utf8(&encrypted).is_err() {
+                    return Err(Error::msg(format!(
+                        "{:?} encrypts to invalid utf-8 {:02X?}",
+                        pair, encrypted
+                    )));
+                }
+                let expected = (c0 & 0x7F, pair.1.map(|c1| c1 & 0x7F));
+                if self.decrypt_char_pair(encrypted) != expected {
+                    return Err(Error::msg(format!("{:?} doesn't decrypt back", pair)));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> Cipher<N> for Layout<N> {
+    fn encrypt_char_pair(&self, pair: BytePair) -> [u8; N] {
+        let mut encrypted = self.prefix;
+        for field in self.fields {
+            let source = match (field.source, pair.1) {
+                (Source::First, _) => pair.0,
+                (Source::Second, Some(c1)) => c1,
+                (Source::Second, None) => continue,
+            };
+            encrypted[field.byte] |= (source >> field.low & mask(field.len)) << field.at;
+        }
+        if pair.1.is_none() {
+            encrypted[self.single.byte] |= bit(self.single);
+        }
+        match self.overlong {
+            Overlong::Never => {}
+            Overlong::Always(overlong) => encrypted[overlong.byte] |= bit(overlong),
+            Overlong::WhenInvalid(overlong) => {
+                if from_utf8(&encrypted).is_err() {
+                    encrypted[overlong.byte] |= bit(overlong);
+                }
+            }
+        }
+        encrypted
+    }
+
+    fn decrypt_char_pair(&self, encrypted: [u8; N]) -> BytePair {
+        let (mut c0, mut c1) = (0, 0);
+        for field in self.fields {
+            let bits = (encrypted[field.byte] >> field.at & mask(field.len)) << field.low;
+            match field.source {
+                Source::First => c0 |= bits,
+                Source::Second => c1 |= bits,
+            }
+        }
+        if encrypted[self.single.byte] & bit(self.single) != 0 {
+            return (c0, None);
+        }
+        (c0, Some(c1))
+    }
+}
+
+fn mask(len: u8) -> u8 {
+    (0xFFu16 >> (8 - len)) as u8
+}
+
+fn bit(bit: Bit) -> u8 {
+    1 << bit.bit
+}
@@ -1,16 +1,22 @@
 mod bytepairs;
+mod enciphered;
 mod extended;
+pub mod layout;
 mod nbytes;
+mod private;
 mod standard;
 #[cfg(test)]
 mod tests;
 
-use anyhow::Result;
+use anyhow::{Error, Result};
 use bytepairs::BytePairs;
 use nbytes::NBytes;
 use std::io::{Read, Write};
+use std::str::from_utf8;
 
+pub use enciphered::Enciphered;
 pub use extended::Extended;
+pub use private::Private;
 pub use standard::Standard;
 
 pub type BytePair = (u8, Option<u8>);
@@ -19,6 +25,12 @@ pub trait Cipher<const N: usize> {
     fn encrypt_char_pair(&self, pair: BytePair) -> [u8; N];
     fn decrypt_char_pair(&self, encrypted: [u8; N]) -> BytePair;
 
+    /// Rejects chars the cipher could never have written. Most ciphers make something of any
+    /// char of the right width, so by default nothing is rejected.
+    fn check_char(&self, _encrypted: [u8; N]) -> Result<()> {
+        Ok(())
+    }
+
     fn encrypt<R, W>(&self, reader: R, mut writer: W) -> Result<()>
     where
         R: Read,
@@ -37,11 +49,74 @@ pub trait Cipher<const N: usize> {
         W: Write,
     {
         for encrypted in NBytes::new(reader) {
-            match self.decrypt_char_pair(encrypted?) {
+            let encrypted = encrypted?;
+            self.check_char(encrypted)?;
+            match self.decrypt_char_pair(encrypted) {
                 (c0, Some(c1)) => writer.write_all(&[c0, c1]),
                 (c0, None) => writer.write_all(&[c0]),
             }?;
         }
         Ok(())
     }
+
+    fn encrypt_to_string(&self, bytes: &[u8]) -> String {
+        let mut encrypted = String::with_capacity(bytes.len().div_ceil(2) * N);
+        for pair in bytes.chunks(2) {
+            let encrypted_char = self.encrypt_char_pair((pair[0], pair.get(1).copied()));
+            // we should always produce valid utf8, if not, that's a bug and we should panic.
+            encrypted.push_str(from_utf8(&encrypted_char).unwrap());
+        }
+        encrypted
+    }
+
+    fn decrypt_to_vec(&self, encrypted: &str) -> Result<Vec<u8>> {
+        let mut decrypted = Vec::with_capacity(encrypted.len() / N * 2);
+        self.decrypt(encrypted.as_bytes(), &mut decrypted)?;
+        Ok(decrypted)
+    }
+
+    fn encrypt_chars<I>(&self, bytes: I) -> impl Iterator<Item = char>
+    where
+        I: IntoIterator<Item = u8>,
+    {
+        let mut bytes = bytes.into_iter().fuse();
+        std::iter::from_fn(move || {
+            let pair = (bytes.next()?, bytes.next());
+            let encrypted = self.encrypt_char_pair(pair);
+            // we should always produce valid utf8, if not, that's a bug and we should panic.
+            from_utf8(&encrypted).unwrap().chars().next()
+        })
+    }
+
+    fn decrypt_chars<I>(&self, encrypted: I) -> impl Iterator<Item = Result<u8>>
+    where
+        I: IntoIterator<Item = char>,
+    {
+        encrypted
+            .into_iter()
+            .flat_map(move |encrypted| {
+                let mut buf = [0; N];
+                if encrypted.len_utf8() != N {
+                    return [Some(Err(wrong_char_width::<N>(encrypted))), None];
+                }
+                encrypted.encode_utf8(&mut buf);
+                if let Err(err) = self.check_char(buf) {
+                    return [Some(Err(err)), None];
+                }
+                match self.decrypt_char_pair(buf) {
+                    (c0, Some(c1)) => [Some(Ok(c0)), Some(Ok(c1))],
+                    (c0, None) => [Some(Ok(c0)), None],
+                }
+            })
+            .flatten()
+    }
+}
+
+pub(crate) fn wrong_char_width<const N: usize>(encrypted: char) -> Error {
+    Error::msg(format!(
+        "expected utf-8 character of {} bytes but found {:?} of {} byte(s)",
+        N,
+        encrypted,
+        encrypted.len_utf8()
+    ))
 }
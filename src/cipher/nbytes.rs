@@ -8,6 +8,8 @@ pub struct NBytes<R: Read, const N: usize> {
 impl<R: Read, const N: usize> NBytes<R, N> {
     pub fn new(reader: R) -> NBytes<R, N> {
         NBytes {
+            // Callers are expected to hand us a buffered (or in-memory) reader.
+            #[allow(clippy::unbuffered_bytes)]
             bytes: reader.bytes(),
         }
     }
@@ -18,9 +20,9 @@ impl<R: Read, const N: usize> Iterator for NBytes<R, N> {
 
     fn next(&mut self) -> Option<Result<[u8; N]>> {
         let mut encrypted = [0; N];
-        for i in 0..N {
+        for (i, slot) in encrypted.iter_mut().enumerate() {
             match self.bytes.next() {
-                Some(Ok(byte)) => encrypted[i] = byte,
+                Some(Ok(byte)) => *slot = byte,
                 None if i == 0 => return None,
                 Some(Err(e)) => return Some(Err(Error::new(e))),
                 None => return Some(insufficent_bytes(i)),
@@ -0,0 +1,87 @@
+use super::{BytePair, Cipher};
+use crate::info::{CipherInfo, Describe, Expansion};
+use anyhow::{Error, Result};
+use std::str::from_utf8;
+
+// Supplementary Private Use Area-A, plane 15.
+const PAIRS: u32 = 0xF_0000;
+// The two pairs that would land on plane 15's noncharacters, and lone trailing bytes, go
+// to the start of Supplementary Private Use Area-B, plane 16.
+const NONCHARACTER_PAIRS: u32 = 0x10_0000;
+const SINGLES: u32 = 0x10_0100;
+const NONCHARACTERS: u32 = 0xFFFE;
+
+/**
+ * Private writes each byte pair as a code point in the supplementary private use areas,
+ * which no real text uses, so ciphertext stands out from anything around it. There's room
+ * for lone trailing bytes to have code points of their own, so it's binary safe.
+ */
+pub struct Private;
+
+impl Describe for Private {
+    const INFO: CipherInfo = CipherInfo {
+        name: "private",
+        id: 12,
+        description: "byte pairs in the supplementary private use areas",
+        expansion: Expansion::Fixed(2.0),
+        output_ranges: &[
+            0xF_0000..=0xF_FFFD,
+            0x10_0000..=0x10_0001,
+            0x10_0100..=0x10_01FF,
+        ],
+        binary_safe: true,
+    };
+}
+
+impl Private {
+    fn code_point(encrypted: [u8; 4]) -> Option<u32> {
+        Some(from_utf8(&encrypted).ok()?.chars().next()? as u32)
+    }
+}
+
+impl Cipher<4> for Private {
+    fn encrypt_char_pair(&self, pair: BytePair) -> [u8; 4] {
+        let code_point = match pair {
+            (c0, Some(c1)) => match u16::from_be_bytes([c0, c1]) as u32 {
+                pair @ NONCHARACTERS.. => NONCHARACTER_PAIRS + pair - NONCHARACTERS,
+                pair => PAIRS + pair,
+            },
+            (c0, None) => SINGLES + c0 as u32,
+        };
+        let mut encrypted = [0; 4];
+        // we should always produce valid utf8, if not, that's a bug and we should panic.
+        char::from_u32(code_point)
+            .unwrap()
+            .encode_utf8(&mut encrypted);
+        encrypted
+    }
+
+    fn decrypt_char_pair(&self, encrypted: [u8; 4]) -> BytePair {
+        let code_point = Private::code_point(encrypted).unwrap_or_default();
+        let pair = match code_point {
+            SINGLES.. => return ((code_point - SINGLES) as u8, None),
+            NONCHARACTER_PAIRS.. => NONCHARACTERS + code_point - NONCHARACTER_PAIRS,
+            // Anything else fails check_char, this just keeps it from underflowing.
+            _ => code_point.wrapping_sub(PAIRS),
+        };
+        let [c0, c1] = (pair as u16).to_be_bytes();
+        (c0, Some(c1))
+    }
+
+    fn check_char(&self, encrypted: [u8; 4]) -> Result<()> {
+        match Private::code_point(encrypted) {
+            Some(code_point)
+                if Private::INFO
+                    .output_ranges
+                    .iter()
+                    .any(|r| r.contains(&code_point)) =>
+            {
+                Ok(())
+            }
+            _ => Err(Error::msg(format!(
+                "{:02X?} isn't a character the private cipher writes",
+                encrypted
+            ))),
+        }
+    }
+}
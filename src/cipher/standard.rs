@@ -1,45 +1,76 @@
+use super::layout::{Bit, Field, Layout, Overlong, Source};
 use super::{BytePair, Cipher};
+use crate::info::{CipherInfo, Describe, Expansion};
 
-const SIG_BIT_MASK: u8 = 0b0100_0000;
-const LOWER_BITS_MASK: u8 = 0b0011_1111;
-const SINGLE_CHAR_MASK: u8 = 0b0000_0100;
-const CHAR_WIDTH_MASK: u8 = 0b1110_0000;
-const CONINUATION_MASK: u8 = 0b1000_0000;
+/**
+ * The significant bit of each byte sits in the leading byte, beside the single byte flag,
+ * and the lower six bits of each fill a continuation byte.
+ *
+ * A leading 0b1110_0000 byte followed by a continuation byte below 0b1010_0000 would be an
+ * overlong encoding. We never use the fourth bit of the leading byte otherwise, so we set it
+ * for those characters to keep them valid utf-8. Decryption never looks at it.
+ */
+pub const LAYOUT: Layout<3> = Layout {
+    prefix: [0b1110_0000, 0b1000_0000, 0b1000_0000],
+    fields: &[
+        Field {
+            source: Source::First,
+            low: 6,
+            len: 1,
+            byte: 0,
+            at: 1,
+        },
+        Field {
+            source: Source::First,
+            low: 0,
+            len: 6,
+            byte: 1,
+            at: 0,
+        },
+        Field {
+            source: Source::Second,
+            low: 6,
+            len: 1,
+            byte: 0,
+            at: 0,
+        },
+        Field {
+            source: Source::Second,
+            low: 0,
+            len: 6,
+            byte: 2,
+            at: 0,
+        },
+    ],
+    single: Bit { byte: 0, bit: 2 },
+    overlong: Overlong::WhenInvalid(Bit { byte: 0, bit: 3 }),
+};
 
 pub struct Standard;
 
+impl Describe for Standard {
+    const INFO: CipherInfo = CipherInfo {
+        name: "standard",
+        id: 1,
+        description: "7-bit byte pairs packed into 3-byte utf-8 characters",
+        expansion: Expansion::Fixed(1.5),
+        // Full pairs, then lone trailing bytes, then the overlong avoidance characters.
+        output_ranges: &[
+            0x0800..=0x3FFF,
+            0x4000..=0x4FC0,
+            0x6000..=0x6FC0,
+            0x8000..=0x87FF,
+        ],
+        binary_safe: false,
+    };
+}
+
 impl Cipher<3> for Standard {
     fn encrypt_char_pair(&self, pair: BytePair) -> [u8; 3] {
-        let mut encrypted_char = [0, 0, 0];
-        let c0 = pair.0;
-        let sig_0 = c0 & SIG_BIT_MASK;
-        let low_0 = c0 & LOWER_BITS_MASK;
-        encrypted_char[0] = CHAR_WIDTH_MASK | (sig_0 >> 5);
-        encrypted_char[1] = CONINUATION_MASK | low_0;
-        if let Some(c1) = pair.1 {
-            let sig_1 = c1 & SIG_BIT_MASK;
-            let low_1 = c1 & LOWER_BITS_MASK;
-            encrypted_char[0] = encrypted_char[0] | (sig_1 >> 6);
-            encrypted_char[2] = CONINUATION_MASK | low_1;
-        } else {
-            encrypted_char[0] = encrypted_char[0] | SINGLE_CHAR_MASK;
-            encrypted_char[2] = CONINUATION_MASK;
-        }
-        return encrypted_char;
+        LAYOUT.encrypt_char_pair(pair)
     }
 
     fn decrypt_char_pair(&self, encrypted: [u8; 3]) -> BytePair {
-        let c0_sig_bit = (encrypted[0] & 2) << 5;
-        let c0_lower = encrypted[1] & LOWER_BITS_MASK;
-        let c0 = c0_sig_bit | c0_lower;
-
-        if encrypted[0] & SINGLE_CHAR_MASK != 0 {
-            return (c0, None);
-        }
-
-        let c1_sig_bit = (encrypted[0] & 1) << 6;
-        let c1_lower = encrypted[2] & LOWER_BITS_MASK;
-        let c1 = c1_sig_bit | c1_lower;
-        return (c0, Some(c1));
+        LAYOUT.decrypt_char_pair(encrypted)
     }
 }
@@ -1,4 +1,6 @@
-use super::{Cipher, Extended, Standard};
+use super::layout::{Bit, Field, Layout, Overlong, Source};
+use super::{extended, standard, BytePair, Cipher, Extended, Private, Standard};
+use crate::info::Describe;
 use anyhow::Result;
 use std::io::Cursor;
 
@@ -17,7 +19,10 @@ macro_rules! cipher_suite {
     ($suite_name:ident,$cipher:expr,$cipher_type:ty) => {
         #[cfg(test)]
         mod $suite_name {
-            use super::{all_ascii_pairs, decrypt_string, encrypt_string, $cipher_type};
+            use super::{
+                all_ascii_pairs, all_seven_bit_pairs, char_iterators, decrypt_string,
+                encrypt_string, string_api, $cipher_type,
+            };
 
             simple_test!(single_pair, $cipher, "ad");
             simple_test!(two_pair, $cipher, "adgc");
@@ -40,12 +45,223 @@ macro_rules! cipher_suite {
             fn ascii_pairs() {
                 all_ascii_pairs($cipher);
             }
+
+            #[test]
+            fn seven_bit_pairs() {
+                all_seven_bit_pairs($cipher);
+            }
+
+            #[test]
+            fn string_and_byte_slice_api() {
+                string_api($cipher, "120 mokneys on 40 barrels");
+                string_api($cipher, "odd\tlength\n");
+                string_api($cipher, "");
+            }
+
+            #[test]
+            fn lazy_char_iterators() {
+                char_iterators($cipher, "hello there");
+                char_iterators($cipher, "bbb");
+            }
         }
     };
 }
 
 cipher_suite!(standard_tests, Standard, Standard);
 cipher_suite!(extended_tests, Extended, Extended);
+cipher_suite!(private_tests, Private, Private);
+
+#[test]
+fn private_keeps_every_byte() {
+    for c0 in 0..=u8::MAX {
+        for c1 in (0..=u8::MAX).map(Some).chain([None]) {
+            let pair = (c0, c1);
+            let encrypted = Private.encrypt_char_pair(pair);
+            let c = std::str::from_utf8(&encrypted)
+                .unwrap()
+                .chars()
+                .next()
+                .unwrap();
+            assert!(Private::INFO.emits(c), "{:?} encrypted to {:?}", pair, c);
+            assert!(Private.check_char(encrypted).is_ok());
+            assert_eq!(pair, Private.decrypt_char_pair(encrypted));
+        }
+    }
+    let bytes = [0x00, 0xFF, 0xFE, 0xFF, 0xFF, 0x00];
+    let encrypted = Private.encrypt_to_string(&bytes);
+    assert_eq!(bytes, Private.decrypt_to_vec(&encrypted).unwrap()[..]);
+}
+
+#[test]
+fn private_rejects_other_text() {
+    for text in [
+        "\u{10000}",
+        "\u{F0000}\u{10FFFD}",
+        "\u{100002}",
+        "\u{1000FF}",
+    ] {
+        assert!(
+            Private.decrypt_to_vec(text).is_err(),
+            "{:?} decrypted",
+            text
+        );
+        assert!(Private
+            .decrypt_chars(text.chars())
+            .any(|byte| byte.is_err()));
+    }
+}
+
+#[test]
+fn layouts_are_valid() {
+    standard::LAYOUT.validate().unwrap();
+    extended::LAYOUT.validate().unwrap();
+}
+
+#[test]
+fn layouts_match_the_original_ciphers() {
+    for c0 in 0..=u8::MAX {
+        for c1 in (0..=u8::MAX).map(Some).chain([None]) {
+            let pair = (c0, c1);
+            let encrypted = Standard.encrypt_char_pair(pair);
+            assert_eq!(original_standard(pair), encrypted, "{:?}", pair);
+            assert_eq!(
+                original_decrypt(encrypted[0], &encrypted[1..]),
+                Standard.decrypt_char_pair(encrypted)
+            );
+            let encrypted = Extended.encrypt_char_pair(pair);
+            assert_eq!(original_extended(pair), encrypted, "{:?}", pair);
+            assert_eq!(
+                original_decrypt(encrypted[1], &encrypted[2..]),
+                Extended.decrypt_char_pair(encrypted)
+            );
+        }
+    }
+}
+
+#[test]
+fn invalid_layouts() {
+    const FIELDS: &[Field] = &[Field {
+        source: Source::First,
+        low: 0,
+        len: 6,
+        byte: 1,
+        at: 0,
+    }];
+    const TWICE: &[Field] = &[
+        FIELDS[0],
+        Field {
+            source: Source::First,
+            low: 5,
+            len: 1,
+            byte: 2,
+            at: 0,
+        },
+    ];
+    const TOO_LONG: &[Field] = &[Field {
+        len: 9,
+        ..FIELDS[0]
+    }];
+    const OVER_THE_TOP: &[Field] = &[Field { at: 4, ..FIELDS[0] }];
+    let valid = standard::LAYOUT;
+    let invalid = [
+        // Loses the top bit of the first byte, and all of the second.
+        Layout {
+            fields: FIELDS,
+            ..valid
+        },
+        // Not a 3 byte char.
+        Layout {
+            prefix: [0b1111_0000, 0b1000_0000, 0b1000_0000],
+            ..valid
+        },
+        Layout {
+            prefix: [0b1110_0000, 0b1100_0000, 0b1000_0000],
+            ..valid
+        },
+        // Chars too small for 3 bytes.
+        Layout {
+            overlong: Overlong::Never,
+            ..valid
+        },
+        // The flag on top of the field.
+        Layout {
+            single: Bit { byte: 1, bit: 0 },
+            ..valid
+        },
+        Layout {
+            single: Bit { byte: 3, bit: 0 },
+            ..valid
+        },
+        Layout {
+            single: Bit { byte: 0, bit: 9 },
+            ..valid
+        },
+        // Over the width marker.
+        Layout {
+            single: Bit { byte: 0, bit: 4 },
+            ..valid
+        },
+        // The same bit twice.
+        Layout {
+            fields: TWICE,
+            ..valid
+        },
+        Layout {
+            fields: TOO_LONG,
+            ..valid
+        },
+        Layout {
+            fields: OVER_THE_TOP,
+            ..valid
+        },
+    ];
+    for layout in invalid {
+        assert!(layout.validate().is_err(), "{:?} was valid", layout);
+    }
+}
+
+// Standard and Extended as they were written before they were described by layouts.
+fn original_standard(pair: BytePair) -> [u8; 3] {
+    let (c0, c1) = pair;
+    let mut encrypted = [0b1110_0000 | (c0 & 0x40) >> 5, 0x80 | (c0 & 0x3F), 0x80];
+    match c1 {
+        Some(c1) => {
+            encrypted[0] |= (c1 & 0x40) >> 6;
+            encrypted[2] |= c1 & 0x3F;
+        }
+        None => encrypted[0] |= 0b0000_0100,
+    }
+    if encrypted[0] == 0b1110_0000 && c0 & 0x3F < 0b0010_0000 {
+        encrypted[0] |= 0b0000_1000;
+    }
+    encrypted
+}
+
+fn original_extended(pair: BytePair) -> [u8; 4] {
+    let (c0, c1) = pair;
+    let mut encrypted = [
+        0b1111_0000,
+        0b1001_0000 | (c0 & 0x40) >> 5,
+        0x80 | (c0 & 0x3F),
+        0x80,
+    ];
+    match c1 {
+        Some(c1) => {
+            encrypted[1] |= (c1 & 0x40) >> 6;
+            encrypted[3] |= c1 & 0x3F;
+        }
+        None => encrypted[1] |= 0b0000_0100,
+    }
+    encrypted
+}
+
+fn original_decrypt(flags: u8, low: &[u8]) -> BytePair {
+    let c0 = (flags & 2) << 5 | low[0] & 0x3F;
+    if flags & 0b0000_0100 != 0 {
+        return (c0, None);
+    }
+    (c0, Some((flags & 1) << 6 | low[1] & 0x3F))
+}
 
 fn all_ascii_pairs<C: Cipher<N>, const N: usize>(cipher: C) {
     for c0 in 0b0010_0000..0b0111_1111 {
@@ -65,6 +281,46 @@ fn all_ascii_pairs<C: Cipher<N>, const N: usize>(cipher: C) {
     }
 }
 
+fn all_seven_bit_pairs<C: Cipher<N>, const N: usize>(cipher: C) {
+    for c0 in 0..0b1000_0000 {
+        for c1 in (0..0b1000_0000).map(Some).chain([None]) {
+            let byte_pair = (c0, c1);
+            let encrypted = cipher.encrypt_char_pair(byte_pair);
+            assert!(
+                std::str::from_utf8(&encrypted).is_ok(),
+                "{:?} encrypted to invalid utf-8 {:?}",
+                byte_pair,
+                encrypted
+            );
+            assert_eq!(byte_pair, cipher.decrypt_char_pair(encrypted));
+        }
+    }
+}
+
+fn string_api<C: Cipher<N>, const N: usize>(cipher: C, plaintext: &str) {
+    let encrypted = cipher.encrypt_to_string(plaintext.as_bytes());
+    let decrypted = cipher
+        .decrypt_to_vec(&encrypted)
+        .expect("decryption failed");
+    assert_eq!(plaintext.as_bytes(), decrypted);
+    let streamed = encrypt_string(plaintext, cipher).expect("encryption failed");
+    assert_eq!(streamed, encrypted);
+}
+
+fn char_iterators<C: Cipher<N>, const N: usize>(cipher: C, plaintext: &str) {
+    let encrypted: String = cipher.encrypt_chars(plaintext.bytes()).collect();
+    assert_eq!(cipher.encrypt_to_string(plaintext.as_bytes()), encrypted);
+    let decrypted: Vec<u8> = cipher
+        .decrypt_chars(encrypted.chars())
+        .collect::<Result<_>>()
+        .expect("decryption failed");
+    assert_eq!(plaintext.as_bytes(), decrypted);
+
+    let endless = std::iter::repeat(b'a');
+    assert_eq!(3, cipher.encrypt_chars(endless).take(3).count());
+    assert!(cipher.decrypt_chars("ab".chars()).next().unwrap().is_err());
+}
+
 fn encrypt_string<C, const N: usize>(to_encrypt: &str, cipher: C) -> Result<String>
 where
     C: Cipher<N>,
@@ -77,13 +333,9 @@ where
 }
 
 fn encrypt_size<const N: usize>(to_encrypt: &str) -> usize {
-    let num_bytes = to_encrypt.bytes().len();
-    let num_encrypted_chars_needed = if num_bytes % 2 == 0 {
-        num_bytes / 2
-    } else {
-        (num_bytes / 2) + 1
-    };
-    return num_encrypted_chars_needed * N;
+    let num_bytes = to_encrypt.len();
+    let num_encrypted_chars_needed = num_bytes.div_ceil(2);
+    num_encrypted_chars_needed * N
 }
 
 fn decrypt_string<C, const N: usize>(to_decrypt: &str, cipher: C) -> Result<String>
@@ -98,7 +350,7 @@ where
 }
 
 fn decrypt_size<const N: usize>(to_decrypt: &str) -> usize {
-    let num_bytes = to_decrypt.bytes().len();
+    let num_bytes = to_decrypt.len();
     let num_encrypted_chars = num_bytes / N;
-    return num_encrypted_chars * 2;
+    num_encrypted_chars * 2
 }
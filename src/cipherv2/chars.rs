@@ -46,7 +46,7 @@ impl<R: Read> Iterator for Chars<R> {
 
 fn char_len(first_byte: u8) -> Result<usize> {
     match first_byte {
-        0b0000_0000..=0b0111_0000 => Ok(1),
+        0b0000_0000..=0b0111_1111 => Ok(1),
         0b1000_0000..=0b1101_1111 => Ok(2),
         0b1110_0000..=0b1110_1111 => Ok(3),
         0b1111_0000..=0b1111_0111 => Ok(4),
@@ -74,4 +74,13 @@ mod tests {
         let res = chars.next().unwrap().expect("got error");
         assert_eq!('k', res);
     }
+
+    #[test]
+    fn all_ascii() {
+        let ascii: String = (0..0b1000_0000u8).map(char::from).collect();
+        let chars: String = Chars::new(Cursor::new(ascii.clone()))
+            .collect::<anyhow::Result<_>>()
+            .expect("got error");
+        assert_eq!(ascii, chars);
+    }
 }
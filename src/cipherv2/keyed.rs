@@ -0,0 +1,137 @@
+use super::{BytePair, CipherV2};
+use crate::crypto::{zeroize, KeyStream, Sha256};
+use crate::info::{CipherInfo, Describe, Expansion};
+
+const PAIR_VALUES: usize = 1 << 16;
+const DERIVATION_ROUNDS: usize = 4096;
+const DERIVATION_CONTEXT: &[u8] = b"unicipher keyed v1";
+// The two values that would land on the U+1FFFE and U+1FFFF noncharacters are moved up to
+// the start of plane 2 instead.
+const PLANE_1: u32 = 0x10000;
+const PLANE_2: u32 = 0x20000;
+const NONCHARACTERS: u32 = 0xFFFE;
+
+/**
+ * Keyed maps every BytePair onto a code point in the Supplementary Multilingual Plane, but
+ * rather than a fixed mapping it uses a permutation shuffled by a key stream derived from a
+ * passphrase. Without the passphrase the ciphertext can't be read by simply running it
+ * through unicipher.
+ */
+pub struct Keyed {
+    encrypt_table: Vec<u16>,
+    decrypt_table: Vec<u16>,
+}
+
+impl Keyed {
+    pub fn new(passphrase: &[u8]) -> Self {
+        let mut key_stream = KeyStream::new(derive_seed(passphrase));
+        let mut encrypt_table: Vec<u16> = (0..PAIR_VALUES).map(|value| value as u16).collect();
+        // Fisher-Yates shuffle.
+        for i in (1..PAIR_VALUES).rev() {
+            let j = key_stream.below(i as u32 + 1) as usize;
+            encrypt_table.swap(i, j);
+        }
+
+        let mut decrypt_table = vec![0; PAIR_VALUES];
+        for (plain, encrypted) in encrypt_table.iter().enumerate() {
+            decrypt_table[*encrypted as usize] = plain as u16;
+        }
+
+        Keyed {
+            encrypt_table,
+            decrypt_table,
+        }
+    }
+}
+
+// The tables are as good as the passphrase to anyone who can read them.
+impl Drop for Keyed {
+    fn drop(&mut self) {
+        zeroize(&mut self.encrypt_table);
+        zeroize(&mut self.decrypt_table);
+    }
+}
+
+impl Describe for Keyed {
+    const INFO: CipherInfo = CipherInfo {
+        name: "keyed",
+        id: 4,
+        description: "byte pairs permuted by a passphrase onto the SMP",
+        expansion: Expansion::Fixed(2.0),
+        output_ranges: &[0x10000..=0x1FFFD, 0x20000..=0x20001],
+        binary_safe: false,
+    };
+}
+
+impl CipherV2 for Keyed {
+    fn encrypt_char_pair(&self, pair: BytePair) -> char {
+        let value = self.encrypt_table[u16::from_be_bytes([pair.0, pair.1]) as usize] as u32;
+        let code_point = if value < NONCHARACTERS {
+            PLANE_1 + value
+        } else {
+            PLANE_2 + value - NONCHARACTERS
+        };
+        // we should always produce valid utf8, if not, that's a bug and we should panic.
+        char::from_u32(code_point).unwrap()
+    }
+
+    fn decrypt_char_pair(&self, encrypted: char) -> BytePair {
+        let code_point = encrypted as u32;
+        let value = if code_point >= PLANE_2 {
+            code_point.wrapping_sub(PLANE_2).wrapping_add(NONCHARACTERS)
+        } else {
+            code_point.wrapping_sub(PLANE_1)
+        };
+        let [c0, c1] = self.decrypt_table[value as u16 as usize].to_be_bytes();
+        (c0, c1)
+    }
+}
+
+/// Stretches the passphrase into a seed by hashing it over and over again, so that trying
+/// out passphrases is at least a little expensive.
+fn derive_seed(passphrase: &[u8]) -> [u8; 32] {
+    let mut seed = [0; 32];
+    let mut hasher = Sha256::new();
+    hasher.update(DERIVATION_CONTEXT);
+    hasher.update(passphrase);
+    for _ in 0..DERIVATION_ROUNDS {
+        let mut round = hasher.clone();
+        round.update(&seed);
+        seed = round.finish();
+    }
+    seed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Keyed;
+    use crate::cipherv2::CipherV2;
+
+    #[test]
+    fn every_pair_round_trips() {
+        let keyed = Keyed::new(b"correct horse battery staple");
+        for c0 in 0..=u8::MAX {
+            for c1 in 0..=u8::MAX {
+                let encrypted = keyed.encrypt_char_pair((c0, c1));
+                assert_eq!((c0, c1), keyed.decrypt_char_pair(encrypted));
+            }
+        }
+    }
+
+    #[test]
+    fn same_key_same_mapping() {
+        let plaintext = "attack at dawn".as_bytes();
+        let a = Keyed::new(b"key").encrypt_to_string(plaintext);
+        let b = Keyed::new(b"key").encrypt_to_string(plaintext);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn different_keys_different_mappings() {
+        let plaintext = "attack at dawn".as_bytes();
+        let encrypted = Keyed::new(b"key").encrypt_to_string(plaintext);
+        let wrong_key = Keyed::new(b"kez");
+        assert_ne!(encrypted, wrong_key.encrypt_to_string(plaintext));
+        assert_ne!(plaintext, wrong_key.decrypt_to_vec(&encrypted).unwrap());
+    }
+}
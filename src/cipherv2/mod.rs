@@ -1,14 +1,16 @@
 mod bytepairs;
 mod chars;
+mod keyed;
 mod simple;
 #[cfg(test)]
 mod tests;
 
 use anyhow::Result;
-use bytepairs::BytePairs;
-use chars::Chars;
+pub(crate) use bytepairs::BytePairs;
+pub(crate) use chars::Chars;
 use std::io::{Read, Write};
 
+pub use keyed::Keyed;
 pub use simple::Simple;
 
 pub type BytePair = (u8, u8);
@@ -16,18 +18,24 @@ pub type BytePair = (u8, u8);
 /**
  * CipherV2 provides several major enhancements over the initial verison of Cipher:
  *   - We don't concern ourselves with odd length strings. We just assume two chars always and
- *   0-pad where necessary (and ignore the 0-pad where necessary).
+ *     0-pad where necessary (and ignore the 0-pad where necessary).
  *   - Encryption and Decryption targets are an actual char. This helps us ensure we're always
- *   encrypting to a valid unicode point, as well as allowing us to write more straight forward
- *   ciphers. We think about the code point we're encrypting/decrypting to/from rather than
- *   thinking about the structure of utf-8 encoding.
+ *     encrypting to a valid unicode point, as well as allowing us to write more straight forward
+ *     ciphers. We think about the code point we're encrypting/decrypting to/from rather than
+ *     thinking about the structure of utf-8 encoding.
  */
 pub trait CipherV2 {
     fn encrypt_char_pair(&self, pair: BytePair) -> char;
     fn decrypt_char_pair(&self, encrypted: char) -> BytePair;
 
+    /// The bits of each byte that survive a round trip through the cipher.
+    fn pair_mask(&self) -> BytePair {
+        (0b1111_1111, 0b1111_1111)
+    }
+
     fn encrypt<R, W>(&self, reader: R, mut writer: W) -> Result<()>
     where
+        Self: Sized,
         R: Read,
         W: Write,
     {
@@ -35,22 +43,69 @@ pub trait CipherV2 {
         for byte_pair in BytePairs::new(reader) {
             let encrypted = self.encrypt_char_pair(byte_pair?);
             let encoded = encrypted.encode_utf8(&mut buf);
-            writer.write_all(&encoded.as_bytes())?;
+            writer.write_all(encoded.as_bytes())?;
         }
         Ok(())
     }
 
     fn decrypt<R, W>(&self, reader: R, mut writer: W) -> Result<()>
     where
+        Self: Sized,
         R: Read,
         W: Write,
     {
         for encrypted in Chars::new(reader) {
             match self.decrypt_char_pair(encrypted?) {
-                (c0, c1) if c1 == 0 => writer.write_all(&[c0]),
+                (c0, 0) => writer.write_all(&[c0]),
                 (c0, c1) => writer.write_all(&[c0, c1]),
             }?;
         }
         Ok(())
     }
+
+    fn encrypt_to_string(&self, bytes: &[u8]) -> String
+    where
+        Self: Sized,
+    {
+        let mut encrypted = String::with_capacity(bytes.len());
+        for pair in bytes.chunks(2) {
+            encrypted.push(self.encrypt_char_pair((pair[0], pair.get(1).copied().unwrap_or(0))));
+        }
+        encrypted
+    }
+
+    fn decrypt_to_vec(&self, encrypted: &str) -> Result<Vec<u8>>
+    where
+        Self: Sized,
+    {
+        let mut decrypted = Vec::with_capacity(encrypted.len());
+        self.decrypt(encrypted.as_bytes(), &mut decrypted)?;
+        Ok(decrypted)
+    }
+
+    fn encrypt_chars<I>(&self, bytes: I) -> impl Iterator<Item = char>
+    where
+        Self: Sized,
+        I: IntoIterator<Item = u8>,
+    {
+        let mut bytes = bytes.into_iter().fuse();
+        std::iter::from_fn(move || {
+            let pair = (bytes.next()?, bytes.next().unwrap_or(0b0000_0000));
+            Some(self.encrypt_char_pair(pair))
+        })
+    }
+
+    fn decrypt_chars<I>(&self, encrypted: I) -> impl Iterator<Item = u8>
+    where
+        Self: Sized,
+        I: IntoIterator<Item = char>,
+    {
+        encrypted
+            .into_iter()
+            .flat_map(move |encrypted| match self.decrypt_char_pair(encrypted) {
+                (c0, 0) => [Some(c0), None],
+                (c0, c1) => [Some(c0), Some(c1)],
+            })
+            .flatten()
+    }
 }
@@ -1,18 +1,29 @@
 use super::{BytePair, CipherV2};
+use crate::info::{CipherInfo, Describe, Expansion};
 
 const C0_MASK: u32 = 0b0011_1111_1000_0000;
 const C1_MASK: u32 = 0b0111_1111;
 
 pub struct Simple;
 
+impl Describe for Simple {
+    const INFO: CipherInfo = CipherInfo {
+        name: "simple",
+        id: 3,
+        description: "7-bit byte pairs shifted into a single code point",
+        expansion: Expansion::Variable { min: 0.5, max: 1.5 },
+        output_ranges: &[0x0000..=0x7FFF],
+        binary_safe: false,
+    };
+}
+
 impl CipherV2 for Simple {
     fn encrypt_char_pair(&self, pair: BytePair) -> char {
         let c0 = pair.0 as u16;
         let c1 = pair.1 as u16;
         let to_encrypt = (c0 << 7) | c1;
-        return char::from_u32(to_encrypt as u32).unwrap(); // we should always produce valid utf8,
-                                                           // if not, that's a bug and we should
-                                                           // panic.
+        // we should always produce valid utf8, if not, that's a bug and we should panic.
+        char::from_u32(to_encrypt as u32).unwrap()
     }
 
     fn decrypt_char_pair(&self, encrypted: char) -> BytePair {
@@ -21,4 +32,8 @@ impl CipherV2 for Simple {
         let c1 = bytes & C1_MASK;
         (c0.to_be_bytes()[3], c1.to_be_bytes()[3])
     }
+
+    fn pair_mask(&self) -> BytePair {
+        (C1_MASK as u8, C1_MASK as u8)
+    }
 }
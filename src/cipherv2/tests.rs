@@ -1,4 +1,4 @@
-use super::{CipherV2, Simple};
+use super::{CipherV2, Keyed, Simple};
 use anyhow::Result;
 use std::io::Cursor;
 
@@ -17,7 +17,10 @@ macro_rules! cipher_suite {
     ($suite_name:ident,$cipher:expr,$cipher_type:ty) => {
         #[cfg(test)]
         mod $suite_name {
-            use super::{all_ascii_pairs, decrypt_string, encrypt_string, $cipher_type};
+            use super::{
+                all_ascii_pairs, char_iterators, decrypt_string, encrypt_string, string_api,
+                $cipher_type,
+            };
 
             simple_test!(single_pair, $cipher, "ad");
             simple_test!(two_pair, $cipher, "adgc");
@@ -40,11 +43,25 @@ macro_rules! cipher_suite {
             fn ascii_pairs() {
                 all_ascii_pairs($cipher);
             }
+
+            #[test]
+            fn string_and_byte_slice_api() {
+                string_api($cipher, "120 mokneys on 40 barrels");
+                string_api($cipher, "odd length");
+                string_api($cipher, "");
+            }
+
+            #[test]
+            fn lazy_char_iterators() {
+                char_iterators($cipher, "hello there");
+                char_iterators($cipher, "bbb");
+            }
         }
     };
 }
 
 cipher_suite!(simple_tests, Simple, Simple);
+cipher_suite!(keyed_tests, Keyed::new(b"test key"), Keyed);
 
 fn all_ascii_pairs<C: CipherV2>(cipher: C) {
     for c0 in 0b0010_0000..0b0111_1111 {
@@ -64,6 +81,26 @@ fn all_ascii_pairs<C: CipherV2>(cipher: C) {
     }
 }
 
+fn string_api<C: CipherV2>(cipher: C, plaintext: &str) {
+    let encrypted = cipher.encrypt_to_string(plaintext.as_bytes());
+    let decrypted = cipher
+        .decrypt_to_vec(&encrypted)
+        .expect("decryption failed");
+    assert_eq!(plaintext.as_bytes(), decrypted);
+    let streamed = encrypt_string(plaintext, cipher).expect("encryption failed");
+    assert_eq!(streamed, encrypted);
+}
+
+fn char_iterators<C: CipherV2>(cipher: C, plaintext: &str) {
+    let encrypted: String = cipher.encrypt_chars(plaintext.bytes()).collect();
+    assert_eq!(cipher.encrypt_to_string(plaintext.as_bytes()), encrypted);
+    let decrypted: Vec<u8> = cipher.decrypt_chars(encrypted.chars()).collect();
+    assert_eq!(plaintext.as_bytes(), decrypted);
+
+    let endless = std::iter::repeat(b'a');
+    assert_eq!(3, cipher.encrypt_chars(endless).take(3).count());
+}
+
 fn encrypt_string<C>(to_encrypt: &str, cipher: C) -> Result<String>
 where
     C: CipherV2,
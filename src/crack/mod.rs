@@ -0,0 +1,86 @@
+mod model;
+mod substitution;
+#[cfg(test)]
+mod tests;
+
+use crate::crypto::sha256;
+use crate::info::CipherInfo;
+use crate::registry::Registry;
+
+pub use model::Bigrams;
+pub use substitution::Solver;
+
+// Below this, a straight decryption is more likely a lucky accident than the real thing.
+const DECRYPTED_CONFIDENCE: f64 = 0.5;
+
+/// What crack thinks a ciphertext says.
+pub struct Guess {
+    /// The cipher the ciphertext looks like it came from, if any.
+    pub cipher: Option<&'static CipherInfo>,
+    /// Whether we had to solve the cipher as a substitution, rather than just decrypt it.
+    pub solved: bool,
+    pub plaintext: Vec<u8>,
+    /// How English the plaintext looks, from 0 to 1.
+    pub confidence: f64,
+}
+
+/**
+ * crack works out what a ciphertext says without being told the cipher or the key. It first
+ * tries every cipher that doesn't need a key and keeps whichever plaintext looks most like
+ * English. If none of them do, it treats the ciphertext as a substitution of byte pairs, as
+ * the keyed cipher is, and solves it with English bigram statistics.
+ */
+pub fn crack(ciphertext: &str) -> Guess {
+    let model = Bigrams::english();
+    let registry = Registry::new();
+
+    let mut best: Option<Guess> = None;
+    for cipher in registry.iter() {
+        let Ok(plaintext) = cipher.decrypt_to_vec(ciphertext) else {
+            continue;
+        };
+        let confidence = model.confidence(model.score(&plaintext));
+        if best
+            .as_ref()
+            .is_none_or(|best| confidence > best.confidence)
+        {
+            best = Some(Guess {
+                cipher: Some(cipher.info()),
+                solved: false,
+                plaintext,
+                confidence,
+            });
+        }
+    }
+    if let Some(best) = best.filter(|best| best.confidence >= DECRYPTED_CONFIDENCE) {
+        return best;
+    }
+
+    let chars: Vec<char> = ciphertext.chars().collect();
+    // The cipher with the fewest characters to choose from is the likeliest fit.
+    let cipher = registry
+        .infos()
+        .filter(|info| !chars.is_empty() && chars.iter().all(|c| info.emits(*c)))
+        .min_by_key(|info| info.output_size());
+    let solver = Solver::new(&model, &chars);
+    // There are only 2^16 byte pairs, so with more symbols than that it can't be a
+    // substitution of them.
+    if solver.symbols() > 1 << 16 {
+        return Guess {
+            cipher,
+            solved: false,
+            plaintext: Vec::new(),
+            confidence: 0.0,
+        };
+    }
+    let plaintext = solver.solve(sha256(ciphertext.as_bytes()));
+    // With few repeated characters there's little to go on, and a high score just means
+    // we've overfitted, so scale the confidence down by how much repetition there is.
+    let repetition = 1.0 - solver.symbols() as f64 / chars.len().max(1) as f64;
+    Guess {
+        cipher,
+        solved: true,
+        confidence: model.confidence(model.score(&plaintext)) * repetition,
+        plaintext,
+    }
+}
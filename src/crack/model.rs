@@ -0,0 +1,112 @@
+use crate::cipherv2::BytePair;
+
+const SAMPLE: &[u8] = include_bytes!("english.txt");
+const BYTES: usize = 256;
+// How much weight unseen bigrams borrow from the plain byte frequencies.
+const SMOOTHING: f64 = 1.0;
+
+/**
+ * Bigrams is a model of which byte follows which in English text, built by counting pairs in
+ * a sample. It scores text by how English it looks, and lists the byte pairs an English
+ * plaintext is most likely to be made of.
+ */
+pub struct Bigrams {
+    // log P(second | first), indexed by the pair as a big endian u16.
+    log_probs: Vec<f64>,
+    // Every pair, most likely first.
+    common_pairs: Vec<BytePair>,
+    // Average scores of the sample itself and of random printable ASCII.
+    english: f64,
+    random: f64,
+}
+
+impl Bigrams {
+    pub fn new(sample: &[u8]) -> Self {
+        let mut counts = vec![0.0; BYTES * BYTES];
+        let mut firsts = vec![0.0; BYTES];
+        let mut singles = vec![0.5; BYTES];
+        for window in sample.windows(2) {
+            counts[index((window[0], window[1]))] += 1.0;
+            firsts[window[0] as usize] += 1.0;
+        }
+        for byte in sample {
+            singles[*byte as usize] += 1.0;
+        }
+        let total: f64 = singles.iter().sum();
+
+        let mut log_probs = vec![0.0; BYTES * BYTES];
+        let mut pair_probs = vec![0.0; BYTES * BYTES];
+        for first in 0..BYTES {
+            for second in 0..BYTES {
+                let i = first * BYTES + second;
+                let single = singles[second] / total;
+                let prob = (counts[i] + SMOOTHING * single) / (firsts[first] + SMOOTHING);
+                log_probs[i] = prob.ln();
+                pair_probs[i] = singles[first] / total * prob;
+            }
+        }
+
+        let mut common_pairs: Vec<BytePair> =
+            (0..=u16::MAX).map(|i| i.to_be_bytes().into()).collect();
+        // Pairs with a byte the sample never uses go last, however the smoothing ranks them.
+        let unseen =
+            |pair: &BytePair| singles[pair.0 as usize] == 0.5 || singles[pair.1 as usize] == 0.5;
+        common_pairs.sort_by(|a, b| {
+            unseen(a)
+                .cmp(&unseen(b))
+                .then(pair_probs[index(*b)].total_cmp(&pair_probs[index(*a)]))
+        });
+
+        let mut bigrams = Bigrams {
+            log_probs,
+            common_pairs,
+            english: 0.0,
+            random: 0.0,
+        };
+        bigrams.english = bigrams.score(sample);
+        let printable = 0x20..0x7f;
+        let mut random = 0.0;
+        for first in printable.clone() {
+            for second in printable.clone() {
+                random += bigrams.log_prob(first, second);
+            }
+        }
+        bigrams.random = random / printable.len().pow(2) as f64;
+        bigrams
+    }
+
+    /// A model of English, built from the sample text shipped with unicipher.
+    pub fn english() -> Self {
+        Bigrams::new(SAMPLE)
+    }
+
+    pub fn log_prob(&self, first: u8, second: u8) -> f64 {
+        self.log_probs[index((first, second))]
+    }
+
+    /// The average log probability of each byte in the text given the one before it.
+    pub fn score(&self, text: &[u8]) -> f64 {
+        if text.len() < 2 {
+            return self.random;
+        }
+        let total: f64 = text
+            .windows(2)
+            .map(|window| self.log_prob(window[0], window[1]))
+            .sum();
+        total / (text.len() - 1) as f64
+    }
+
+    /// Where a score falls between random printable characters (0) and the sample text (1).
+    pub fn confidence(&self, score: f64) -> f64 {
+        ((score - self.random) / (self.english - self.random)).clamp(0.0, 1.0)
+    }
+
+    /// Every possible byte pair, most likely first.
+    pub fn common_pairs(&self) -> &[BytePair] {
+        &self.common_pairs
+    }
+}
+
+pub(crate) fn index(pair: BytePair) -> usize {
+    u16::from_be_bytes([pair.0, pair.1]) as usize
+}
@@ -0,0 +1,227 @@
+use super::model::{index, Bigrams};
+use crate::cipherv2::BytePair;
+use std::collections::HashMap;
+
+const RESTARTS: usize = 4;
+const ITERATIONS: usize = 1_000_000;
+// Only the likeliest pairs are worth trying, English rarely uses the rest.
+const CANDIDATES: usize = 1024;
+// Early on we accept changes that make things worse fairly often, to get out of dead ends.
+const START_TEMPERATURE: f64 = 10.0;
+
+/**
+ * Solver breaks a substitution of byte pairs for characters, which is all the keyed cipher
+ * really is. It starts by matching the most frequent characters with the most frequent
+ * English pairs, then hill climbs: it keeps trying to move a character onto another pair,
+ * or swap the pairs of two characters, and keeps changes that make the plaintext look more
+ * like English. Simulated annealing lets it take the odd step backwards too, less and less
+ * often as it goes, so it doesn't get stuck on the first plausible answer.
+ */
+pub struct Solver<'a> {
+    model: &'a Bigrams,
+    // How many characters of ciphertext there are.
+    len: usize,
+    // Where each symbol appears in the text, most frequent symbol first.
+    occurrences: Vec<Vec<usize>>,
+    // The plaintext bigrams each symbol is part of, by the index of their second byte.
+    bigrams: Vec<Vec<usize>>,
+}
+
+impl<'a> Solver<'a> {
+    pub fn new(model: &'a Bigrams, ciphertext: &[char]) -> Self {
+        let mut positions: HashMap<char, Vec<usize>> = HashMap::new();
+        for (i, c) in ciphertext.iter().enumerate() {
+            positions.entry(*c).or_default().push(i);
+        }
+        let mut occurrences: Vec<(char, Vec<usize>)> = positions.into_iter().collect();
+        // Break ties on the char so the same ciphertext always solves the same way.
+        occurrences.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(&b.0)));
+
+        let occurrences: Vec<Vec<usize>> = occurrences
+            .into_iter()
+            .map(|(_, positions)| positions)
+            .collect();
+        let plain_len = ciphertext.len() * 2;
+        let bigrams = occurrences
+            .iter()
+            .map(|positions| {
+                let mut bigrams: Vec<usize> = positions
+                    .iter()
+                    .flat_map(|position| [2 * position, 2 * position + 1, 2 * position + 2])
+                    .filter(|i| *i > 0 && *i < plain_len)
+                    .collect();
+                bigrams.dedup();
+                bigrams
+            })
+            .collect();
+        Solver {
+            model,
+            len: ciphertext.len(),
+            occurrences,
+            bigrams,
+        }
+    }
+
+    pub fn symbols(&self) -> usize {
+        self.occurrences.len()
+    }
+
+    /// The most English looking plaintext we could find.
+    pub fn solve(&self, seed: [u8; 32]) -> Vec<u8> {
+        if self.len == 0 {
+            return Vec::new();
+        }
+        let mut random = SplitMix::new(u64::from_be_bytes(seed[..8].try_into().unwrap()));
+        let mut best: Option<(f64, Vec<u8>)> = None;
+        for _ in 0..RESTARTS {
+            let (score, plain) = State::new(self).anneal(self, &mut random);
+            if best.as_ref().is_none_or(|(best, _)| score > *best) {
+                best = Some((score, plain));
+            }
+        }
+        best.map(|(_, plain)| plain).unwrap_or_default()
+    }
+}
+
+struct State {
+    mapping: Vec<BytePair>,
+    // Which symbol, if any, each pair is mapped from.
+    owners: Vec<Option<usize>>,
+    plain: Vec<u8>,
+}
+
+impl State {
+    fn new(solver: &Solver) -> Self {
+        let mut state = State {
+            mapping: solver.model.common_pairs()[..solver.symbols()].to_vec(),
+            owners: vec![None; 1 << 16],
+            plain: vec![0; solver.len * 2],
+        };
+        for symbol in 0..solver.symbols() {
+            state.owners[index(state.mapping[symbol])] = Some(symbol);
+            state.write(solver, symbol);
+        }
+        state
+    }
+
+    /// Anneals towards the likeliest plaintext, returning where it ends up along with its
+    /// total log probability.
+    fn anneal(&mut self, solver: &Solver, random: &mut SplitMix) -> (f64, Vec<u8>) {
+        let candidates = &solver.model.common_pairs()[..CANDIDATES.max(solver.symbols())];
+        let mut affected = Vec::new();
+        let mut score = solver.model.score(&self.plain) * (self.plain.len() - 1) as f64;
+        for i in 0..ITERATIONS {
+            let symbol = random.below(solver.symbols());
+            // Half the time swap with another character, otherwise try any likely pair.
+            let pair = if random.below(2) == 0 {
+                self.mapping[random.below(solver.symbols())]
+            } else {
+                candidates[random.below(candidates.len())]
+            };
+            if self.mapping[symbol] == pair {
+                continue;
+            }
+            let other = self.owners[index(pair)];
+
+            affected.clear();
+            match other {
+                Some(other) => merge(
+                    &solver.bigrams[symbol],
+                    &solver.bigrams[other],
+                    &mut affected,
+                ),
+                None => affected.extend_from_slice(&solver.bigrams[symbol]),
+            }
+            let before = self.score(solver, &affected);
+            let old = self.mapping[symbol];
+            self.reassign(solver, symbol, pair, other);
+            let change = self.score(solver, &affected) - before;
+            let temperature = START_TEMPERATURE * (1.0 - i as f64 / ITERATIONS as f64);
+            let chance = random.chance();
+            if change > 0.0 || chance < (change / temperature).exp() {
+                score += change;
+            } else {
+                self.reassign(solver, symbol, old, other);
+            }
+        }
+        (score, self.plain.clone())
+    }
+
+    // Sum of the log probabilities of the bigrams ending at each of the given plain indexes.
+    fn score(&self, solver: &Solver, affected: &[usize]) -> f64 {
+        affected
+            .iter()
+            .map(|i| solver.model.log_prob(self.plain[i - 1], self.plain[*i]))
+            .sum()
+    }
+
+    // Moves symbol onto pair, swapping with the other symbol already there, if any.
+    fn reassign(&mut self, solver: &Solver, symbol: usize, pair: BytePair, other: Option<usize>) {
+        let old = self.mapping[symbol];
+        self.owners[index(old)] = None;
+        if let Some(other) = other {
+            self.mapping[other] = old;
+            self.owners[index(old)] = Some(other);
+            self.write(solver, other);
+        }
+        self.mapping[symbol] = pair;
+        self.owners[index(pair)] = Some(symbol);
+        self.write(solver, symbol);
+    }
+
+    fn write(&mut self, solver: &Solver, symbol: usize) {
+        let (first, second) = self.mapping[symbol];
+        for position in &solver.occurrences[symbol] {
+            self.plain[2 * position] = first;
+            self.plain[2 * position + 1] = second;
+        }
+    }
+}
+
+// Merges two sorted lists of bigrams, dropping any that are in both.
+fn merge(a: &[usize], b: &[usize], merged: &mut Vec<usize>) {
+    let (mut a, mut b) = (a.iter().peekable(), b.iter().peekable());
+    loop {
+        let next = match (a.peek(), b.peek()) {
+            (Some(x), Some(y)) if x < y => a.next(),
+            (Some(x), Some(y)) if x > y => b.next(),
+            (Some(_), Some(_)) => {
+                b.next();
+                a.next()
+            }
+            (Some(_), None) => a.next(),
+            (None, Some(_)) => b.next(),
+            (None, None) => return,
+        };
+        merged.extend(next);
+    }
+}
+
+/// SplitMix64. The solver burns through millions of random numbers, far too many to get from
+/// a KeyStream, and doesn't need them to be unpredictable.
+struct SplitMix {
+    state: u64,
+}
+
+impl SplitMix {
+    fn new(seed: u64) -> Self {
+        SplitMix { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Close enough to uniform for bounds this small.
+    fn below(&mut self, bound: usize) -> usize {
+        (((self.next_u64() >> 32) * bound as u64) >> 32) as usize
+    }
+
+    fn chance(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
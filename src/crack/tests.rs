@@ -0,0 +1,107 @@
+use super::{crack, Bigrams};
+use crate::cipherv2::{CipherV2, Keyed};
+use crate::registry::Registry;
+
+const PLAINTEXT: &str = "\
+It was late in the evening when the train finally pulled into the station, and the \
+platform was almost empty. A porter was sweeping up near the ticket office, and a pair of \
+pigeons were picking at the crumbs under a bench. Anna stepped down with her suitcase and \
+looked around for her uncle, who had promised in his last letter that he would be there to \
+meet her. There was no sign of him. She waited for a while under the clock, watching the \
+lights of the town come on one by one across the valley, and then she decided that she \
+would walk. It was not far, he had said, only a mile or so along the river and then up the \
+hill past the old mill. She had walked further than that many times before. The road was \
+dark but the sky was clear, and there was enough light from the moon to see the way. As \
+she walked she thought about the house, which she had only ever seen in a photograph, and \
+about the cousins she had never met, and about what her mother would have said if she could \
+see her now, alone on a country road at night with everything she owned in one bag. \
+The mill was a dark shape against the stars when she reached it, its wheel long since \
+stopped and its windows boarded over. Beyond it the lane climbed steeply between high \
+hedges, and she had to stop twice to change hands on the suitcase. At the top there was a \
+gate, and beyond the gate a farmhouse with a single lamp burning in the kitchen window. \
+She stood at the gate for a moment, getting her breath back and wondering whether she had \
+come to the right place, and then the door opened and a tall man came out into the yard \
+with a lantern held up in front of him. He called her name, and when she answered he \
+laughed with relief and hurried down to meet her. He had gone to the wrong station, he \
+said, the one on the other side of the valley, because he had read the timetable upside \
+down. He took the suitcase from her and carried it up to the house as if it weighed \
+nothing at all, talking the whole way about the weather and the harvest and the new calf \
+that had been born that morning, and by the time they reached the kitchen door she was \
+laughing too. Inside, the room was warm and smelled of bread, and there were four faces \
+around the table looking up at her with open curiosity. Her aunt got up and kissed her on \
+both cheeks, and one of the children was sent to fetch another chair, and a bowl of soup \
+appeared in front of her before she had even taken off her coat. Nobody asked her any \
+difficult questions that night. They let her eat, and they told her about themselves, and \
+when she began to fall asleep at the table her aunt took her up to a small room under the \
+roof where the bed had been made up with clean sheets and an extra blanket. She lay awake \
+for a while listening to the unfamiliar sounds of the house settling around her, the wind \
+in the chimney and the cows shifting in the barn, and she thought that perhaps, after all, \
+she was going to be all right here.";
+
+#[test]
+fn english_scores_higher_than_noise() {
+    let model = Bigrams::english();
+    let english = model.score(PLAINTEXT.as_bytes());
+    let noise: Vec<u8> = (0..1000u32).map(|i| (i * 37 % 95 + 32) as u8).collect();
+    assert!(english > model.score(&noise));
+    assert!(model.confidence(english) > 0.8);
+    assert!(model.confidence(model.score(&noise)) < 0.2);
+}
+
+#[test]
+fn common_pairs_look_english() {
+    let model = Bigrams::english();
+    let top: Vec<(u8, u8)> = model.common_pairs()[..20].to_vec();
+    assert!(top.contains(&(b'e', b' ')));
+    assert!(top.contains(&(b't', b'h')));
+    assert_eq!(1 << 16, model.common_pairs().len());
+}
+
+#[test]
+fn cracks_unkeyed_ciphers() {
+    for cipher in Registry::new().iter() {
+        let guess = crack(&cipher.encrypt_to_string(PLAINTEXT.as_bytes()));
+        assert_eq!(Some(cipher.info().name), guess.cipher.map(|info| info.name));
+        assert!(!guess.solved);
+        assert_eq!(PLAINTEXT.as_bytes(), guess.plaintext);
+        assert!(guess.confidence > 0.8);
+    }
+}
+
+#[test]
+fn empty_ciphertext() {
+    let guess = crack("");
+    assert!(guess.plaintext.is_empty());
+    assert_eq!(0.0, guess.confidence);
+}
+
+#[test]
+fn too_many_symbols_for_a_substitution() {
+    let ciphertext: String = (0x2_0000..)
+        .filter_map(char::from_u32)
+        .take(70_000)
+        .collect();
+    let guess = crack(&ciphertext);
+    assert!(!guess.solved);
+    assert!(guess.plaintext.is_empty());
+    assert_eq!(0.0, guess.confidence);
+}
+
+#[test]
+fn solves_keyed_substitutions() {
+    let keyed = Keyed::new(b"not really secret");
+    let guess = crack(&keyed.encrypt_to_string(PLAINTEXT.as_bytes()));
+    assert_eq!(Some("keyed"), guess.cipher.map(|info| info.name));
+    assert!(guess.solved);
+    // An odd length message gains a byte, we can't tell the zero padding from a real pair.
+    assert_eq!(PLAINTEXT.len().div_ceil(2) * 2, guess.plaintext.len());
+    let right = PLAINTEXT
+        .bytes()
+        .zip(&guess.plaintext)
+        .filter(|(a, b)| a == *b)
+        .count();
+    // Bigrams alone can't pin down every pair from a few pages, but the common ones give
+    // away plenty.
+    assert!(right * 3 > PLAINTEXT.len(), "only got {} right", right);
+    assert!(guess.confidence > 0.3);
+}
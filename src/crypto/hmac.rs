@@ -0,0 +1,95 @@
+use super::{sha256, Sha256};
+
+const BLOCK_LEN: usize = 64;
+
+/**
+ * HMAC-SHA256, as described in RFC 2104. Keys longer than a block are hashed down first,
+ * shorter ones are zero padded.
+ */
+#[derive(Clone)]
+pub struct Hmac {
+    inner: Sha256,
+    outer: Sha256,
+}
+
+impl Hmac {
+    pub fn new(key: &[u8]) -> Self {
+        let mut block = [0; BLOCK_LEN];
+        if key.len() > BLOCK_LEN {
+            block[..32].copy_from_slice(&sha256(key));
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+        let mut inner = Sha256::new();
+        inner.update(&block.map(|byte| byte ^ 0x36));
+        let mut outer = Sha256::new();
+        outer.update(&block.map(|byte| byte ^ 0x5c));
+        Hmac { inner, outer }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    pub fn finish(self) -> [u8; 32] {
+        let mut outer = self.outer;
+        outer.update(&self.inner.finish());
+        outer.finish()
+    }
+}
+
+pub fn hmac(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut hmac = Hmac::new(key);
+    hmac.update(data);
+    hmac.finish()
+}
+
+/// Compares two tags without bailing out at the first difference, so the time taken doesn't
+/// give away how much of a forged tag was right.
+pub fn tags_match(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hmac, tags_match, Hmac};
+
+    fn hex(digest: [u8; 32]) -> String {
+        digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    // Test cases 1, 2 and 6 from RFC 4231.
+    #[test]
+    fn known_tags() {
+        assert_eq!(
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+            hex(hmac(&[0x0b; 20], b"Hi There"))
+        );
+        assert_eq!(
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            hex(hmac(b"Jefe", b"what do ya want for nothing?"))
+        );
+        assert_eq!(
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+            hex(hmac(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            ))
+        );
+    }
+
+    #[test]
+    fn split_updates_match_one_shot() {
+        let mut split = Hmac::new(b"key");
+        split.update(b"hello ");
+        split.update(b"there");
+        assert_eq!(hmac(b"key", b"hello there"), split.finish());
+    }
+
+    #[test]
+    fn comparing_tags() {
+        assert!(tags_match(b"same", b"same"));
+        assert!(!tags_match(b"same", b"sane"));
+        assert!(!tags_match(b"same", b"sam"));
+    }
+}
@@ -0,0 +1,85 @@
+use super::{zeroize, Sha256};
+
+/**
+ * KeyStream turns a 32 byte seed into an endless stream of pseudo random numbers by hashing
+ * the seed together with a block counter. The same seed always gives the same stream.
+ */
+pub struct KeyStream {
+    seed: [u8; 32],
+    counter: u64,
+    block: [u8; 32],
+    used: usize,
+}
+
+impl KeyStream {
+    pub fn new(seed: [u8; 32]) -> Self {
+        KeyStream {
+            seed,
+            counter: 0,
+            block: [0; 32],
+            used: 32,
+        }
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        if self.used == self.block.len() {
+            let mut hasher = Sha256::new();
+            hasher.update(&self.seed);
+            hasher.update(&self.counter.to_be_bytes());
+            self.block = hasher.finish();
+            self.counter += 1;
+            self.used = 0;
+        }
+        let bytes = &self.block[self.used..self.used + 4];
+        self.used += 4;
+        u32::from_be_bytes(bytes.try_into().unwrap())
+    }
+
+    /// A uniformly distributed number in `0..bound`.
+    pub fn below(&mut self, bound: u32) -> u32 {
+        assert!(bound > 0, "bound must be positive");
+        // Reject the top sliver of the u32 range that doesn't divide evenly into bound,
+        // otherwise small results would come up slightly more often than large ones.
+        let limit = u32::MAX - u32::MAX % bound;
+        loop {
+            let value = self.next_u32();
+            if value < limit {
+                return value % bound;
+            }
+        }
+    }
+}
+
+impl Drop for KeyStream {
+    fn drop(&mut self) {
+        zeroize(&mut self.seed);
+        zeroize(&mut self.block);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeyStream;
+
+    #[test]
+    fn deterministic() {
+        let mut a = KeyStream::new([7; 32]);
+        let mut b = KeyStream::new([7; 32]);
+        let mut c = KeyStream::new([8; 32]);
+        let a: Vec<u32> = (0..20).map(|_| a.next_u32()).collect();
+        let b: Vec<u32> = (0..20).map(|_| b.next_u32()).collect();
+        let c: Vec<u32> = (0..20).map(|_| c.next_u32()).collect();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn below_stays_in_bounds() {
+        let mut stream = KeyStream::new([1; 32]);
+        for bound in [1, 2, 3, 7, 1000, u32::MAX] {
+            for _ in 0..100 {
+                assert!(stream.below(bound) < bound);
+            }
+        }
+    }
+}
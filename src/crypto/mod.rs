@@ -0,0 +1,11 @@
+mod hmac;
+mod keystream;
+mod random;
+mod secret;
+mod sha256;
+
+pub use hmac::{hmac, tags_match, Hmac};
+pub use keystream::KeyStream;
+pub use random::random_seed;
+pub use secret::{zeroize, Secret};
+pub use sha256::{sha256, Sha256};
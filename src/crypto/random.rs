@@ -0,0 +1,20 @@
+use super::Sha256;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A fresh, unpredictable seed for IVs and salts. std seeds every RandomState from the
+/// operating system's random source, which is plenty for what unicipher is.
+pub fn random_seed() -> [u8; 32] {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    let mut hasher = Sha256::new();
+    for _ in 0..4 {
+        let mut state = RandomState::new().build_hasher();
+        state.write_u128(nanos);
+        hasher.update(&state.finish().to_be_bytes());
+    }
+    hasher.finish()
+}
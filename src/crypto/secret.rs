@@ -0,0 +1,88 @@
+use std::fmt::{self, Debug, Formatter};
+use std::ops::Deref;
+use std::ptr;
+use std::sync::atomic::{compiler_fence, Ordering};
+
+/// Overwrites values with their default, in a way the compiler won't optimise away just
+/// because nothing reads them afterwards.
+pub fn zeroize<T: Default>(values: &mut [T]) {
+    for value in values.iter_mut() {
+        // Safe as value is a valid, aligned, exclusive reference.
+        unsafe { ptr::write_volatile(value, T::default()) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+/**
+ * Secret holds key material such as a passphrase and zeroes it when dropped, so it doesn't
+ * linger in freed memory. Its Debug output never shows the contents.
+ */
+pub struct Secret(Vec<u8>);
+
+impl Secret {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Secret(bytes)
+    }
+
+    /// For filling a secret in place. Anything that makes the vec reallocate leaves the old
+    /// allocation behind unzeroed, so reserve enough room up front where that matters.
+    pub fn as_mut_vec(&mut self) -> &mut Vec<u8> {
+        &mut self.0
+    }
+}
+
+impl From<Vec<u8>> for Secret {
+    fn from(bytes: Vec<u8>) -> Self {
+        Secret::new(bytes)
+    }
+}
+
+impl From<String> for Secret {
+    fn from(string: String) -> Self {
+        Secret::new(string.into_bytes())
+    }
+}
+
+impl Deref for Secret {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Debug for Secret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret({} bytes)", self.0.len())
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        // Clear the spare capacity too, it may hold bytes from before the vec last shrank.
+        self.0.resize(self.0.capacity(), 0);
+        zeroize(&mut self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{zeroize, Secret};
+
+    #[test]
+    fn zeroize_clears_values() {
+        let mut bytes = *b"secret";
+        zeroize(&mut bytes);
+        assert_eq!([0; 6], bytes);
+        let mut words = [0xdead_u16, 0xbeef];
+        zeroize(&mut words);
+        assert_eq!([0, 0], words);
+    }
+
+    #[test]
+    fn secret_hides_its_contents() {
+        let secret = Secret::from(String::from("hunter2"));
+        assert_eq!(b"hunter2", &*secret);
+        assert_eq!("Secret(7 bytes)", format!("{:?}", secret));
+    }
+}
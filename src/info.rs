@@ -0,0 +1,55 @@
+use std::fmt::{self, Display, Formatter};
+use std::ops::RangeInclusive;
+
+/**
+ * CipherInfo is the description a cipher gives of itself: how it's named, how much bigger
+ * it makes its input and which characters it can produce.
+ */
+pub struct CipherInfo {
+    /// Stable name used to pick the cipher on the command line.
+    pub name: &'static str,
+    /// Stable numeric ID, never reused once assigned.
+    pub id: u8,
+    pub description: &'static str,
+    /// Bytes of utf-8 output per byte of plaintext.
+    pub expansion: Expansion,
+    /// Code point ranges covering every character the cipher can emit.
+    pub output_ranges: &'static [RangeInclusive<u32>],
+    /// Whether every possible byte sequence survives a round trip.
+    pub binary_safe: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Expansion {
+    Fixed(f32),
+    Variable { min: f32, max: f32 },
+}
+
+pub trait Describe {
+    const INFO: CipherInfo;
+}
+
+impl CipherInfo {
+    /// The number of distinct code points covered by the output ranges.
+    pub fn output_size(&self) -> u32 {
+        self.output_ranges
+            .iter()
+            .map(|range| range.end() - range.start() + 1)
+            .sum()
+    }
+
+    pub fn emits(&self, c: char) -> bool {
+        self.output_ranges
+            .iter()
+            .any(|range| range.contains(&(c as u32)))
+    }
+}
+
+impl Display for Expansion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Expansion::Fixed(ratio) => write!(f, "{:.2}", ratio),
+            Expansion::Variable { min, max } => write!(f, "{:.2}-{:.2}", min, max),
+        }
+    }
+}
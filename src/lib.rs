@@ -0,0 +1,9 @@
+pub mod cipher;
+pub mod cipherv2;
+pub mod crack;
+pub mod crypto;
+pub mod info;
+pub mod modes;
+pub mod pipeline;
+pub mod registry;
+pub mod stream;
@@ -1,26 +1,90 @@
-mod cipher;
-mod cipherv2;
+mod passphrase;
 
-use anyhow::Result;
-use cipher::{Cipher, Extended, Standard};
-use cipherv2::{CipherV2, Simple};
+use anyhow::{Error, Result};
 use clap::{ArgGroup, Parser};
+use passphrase::KeySource;
+use std::fmt;
 use std::fs::{File, OpenOptions};
 use std::io::{stdin, stdout, BufReader, BufWriter, Cursor, Read, Stdin, Stdout, Write};
 use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use unicipher::crypto::{sha256, Secret};
+use unicipher::modes::{Chained, Padded, Padding, Positional, Salted, TagMismatch, Tagged};
+use unicipher::pipeline::{Pipeline, ENVELOPE};
+use unicipher::registry::{transcode, DynCipher, Registry};
+use unicipher::stream::{CustomAlphabet, CUSTOM_PREFIX};
+
+const KEY_VAR: &str = "UNICIPHER_KEY";
+
+// Exit code for a message whose tag doesn't match, so scripts can tell forgery apart from
+// other failures.
+const TAMPERED: u8 = 3;
 
 #[derive(clap::Parser)]
 #[command(author, version, about, long_about = None)]
 #[command(group(ArgGroup::new("action").multiple(false).required(true)))]
+#[command(subcommand_negates_reqs = true, args_conflicts_with_subcommands = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(short, long, group = "action")]
     decrypt: bool,
 
     #[arg(short, long, group = "action")]
     encrypt: bool,
 
-    #[arg(value_enum, short, long, default_value_t = CipherType::Standard)]
-    cipher: CipherType,
+    /// Name or numeric ID of the cipher to use
+    #[arg(short, long, default_value = "standard")]
+    cipher: String,
+
+    /// How the cipher's output is varied across the message
+    #[arg(value_enum, short, long, default_value_t = Mode::Plain)]
+    mode: Mode,
+
+    /// Seed the randomness of the chained and salted modes, for reproducible output
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Pad the message out to a multiple of this many bytes, or to a power of two with pow2
+    #[arg(short, long)]
+    pad: Option<Padding>,
+
+    /// Stages to run the message through in order, such as pad:64,keyed,simple. Decrypting
+    /// needs no flags, the pipeline is recorded in the output
+    #[arg(long, conflicts_with_all = ["mode", "pad", "tag", "seed"])]
+    pipeline: Option<String>,
+
+    /// Compress the message before encrypting it, when that makes it shorter. Decrypting
+    /// needs no flags, the compression is recorded in the output
+    #[arg(long, conflicts_with_all = ["pipeline", "tag", "seed"])]
+    compress: bool,
+
+    /// Build the cipher from the characters in this file, or from the ranges it lists if it
+    /// ends in .toml. The output records which alphabet it used, so decrypt with the same file
+    #[arg(long, conflicts_with_all = ["cipher", "tag", "seed"])]
+    alphabet_file: Option<PathBuf>,
+
+    /// Append a tag keyed with the passphrase, and check it when decrypting
+    #[arg(short, long)]
+    tag: bool,
+
+    /// Passphrase for keyed ciphers. This shows up in shell history and ps, prefer
+    /// --key-file, UNICIPHER_KEY or the prompt you get when no key is given
+    #[arg(short, long, global = true, conflicts_with = "key_file")]
+    key: Option<String>,
+
+    // Where --key ends up once it's been parsed, so it's zeroed when we're done with it.
+    #[arg(skip)]
+    passphrase: Option<Secret>,
+
+    /// File holding the passphrase for keyed ciphers, which mustn't be world-readable
+    #[arg(long, global = true)]
+    key_file: Option<PathBuf>,
+
+    /// Report what's going on, such as where the key came from, on stderr
+    #[arg(short, long, global = true)]
+    verbose: bool,
 
     #[arg(short = 'f', long)]
     input_file: Option<PathBuf>,
@@ -32,19 +96,96 @@ struct Cli {
 }
 
 #[derive(clap::ValueEnum, Clone)]
-enum CipherType {
-    Standard,
-    Extended,
-    Simple,
+enum Mode {
+    /// Each byte pair always encrypts to the same character
+    Plain,
+    /// Rotate each character through the cipher's alphabet based on its position
+    Positional,
+    /// Chain each character to the one before it, starting from a random header
+    Chained,
+    /// Start with a random salt that changes every character after it
+    Salted,
 }
 
-fn main() -> Result<()> {
-    let args = Cli::parse();
-    args.run()
+#[derive(clap::Subcommand)]
+enum Command {
+    /// List the available ciphers
+    List,
+    /// Guess the plaintext of a message without its cipher or key, to show how weak they are
+    Crack {
+        #[arg(short = 'f', long)]
+        input_file: Option<PathBuf>,
+
+        input: Option<String>,
+    },
+    /// Move ciphertext from one cipher to another without writing out the plaintext
+    Transcode {
+        /// Name or numeric ID of the cipher the ciphertext is in
+        #[arg(long)]
+        from: String,
+
+        /// Name or numeric ID of the cipher to move it to
+        #[arg(long)]
+        to: String,
+
+        #[arg(short = 'f', long)]
+        input_file: Option<PathBuf>,
+
+        #[arg(short, long)]
+        output_file: Option<PathBuf>,
+
+        input: Option<String>,
+    },
+}
+
+fn main() -> ExitCode {
+    let mut args = Cli::parse();
+    // Taking over the String's buffer rather than copying it leaves nothing behind unzeroed.
+    args.passphrase = args.key.take().map(Secret::from);
+    match args.run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {:?}", err);
+            if err.is::<TagMismatch>() {
+                ExitCode::from(TAMPERED)
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+    }
 }
 
 impl Cli {
     fn run(&self) -> Result<()> {
+        match &self.command {
+            Some(Command::List) => return list(&Registry::new()),
+            Some(Command::Crack { input_file, input }) => {
+                return match (input_file, input) {
+                    (Some(input_file), _) => crack(from_file(input_file)?),
+                    (None, Some(input)) => crack(from_input(input)),
+                    (None, None) => crack(from_stdin()),
+                };
+            }
+            Some(Command::Transcode {
+                from,
+                to,
+                input_file,
+                output_file,
+                input,
+            }) => {
+                let reader: Box<dyn Read> = match (input_file, input) {
+                    (Some(input_file), _) => Box::new(from_file(input_file)?),
+                    (None, Some(input)) => Box::new(from_input(input)),
+                    (None, None) => Box::new(from_stdin()),
+                };
+                return match output_file {
+                    Some(output_file) => self.transcode(from, to, reader, to_file(output_file)?),
+                    None => self.transcode(from, to, reader, to_stdout()),
+                };
+            }
+            None => {}
+        }
+
         if let (Some(input_file), Some(output_file)) = (&self.input_file, &self.output_file) {
             self.write(from_file(input_file)?, to_file(output_file)?)
         } else if let (Some(input), Some(output_file)) = (&self.input, &self.output_file) {
@@ -60,47 +201,274 @@ impl Cli {
         }
     }
 
-    fn write<R, W>(&self, reader: R, mut writer: W) -> Result<()>
+    fn report(&self, message: fmt::Arguments) {
+        if self.verbose {
+            eprintln!("{}", message);
+        }
+    }
+
+    fn write<R, W>(&self, mut reader: R, writer: W) -> Result<()>
+    where
+        R: Read,
+        W: Write + Finish,
+    {
+        if let Some(spec) = &self.pipeline {
+            if self.encrypt {
+                return self.seal(spec, reader, writer);
+            }
+        }
+        let custom = self.alphabet_file.is_some();
+        if (self.compress || custom) && self.encrypt {
+            if !matches!(self.mode, Mode::Plain) {
+                return Err(Error::msg(
+                    "compression and custom alphabets only work with the plain mode",
+                ));
+            }
+            let mut stages = Vec::new();
+            if self.compress {
+                stages.push("compress".to_string());
+            }
+            match self.pad {
+                Some(Padding::Block(block)) => stages.push(format!("pad:{}", block)),
+                Some(Padding::PowerOfTwo) => stages.push("pad:pow2".to_string()),
+                None => {}
+            }
+            if custom {
+                stages.push(CUSTOM_PREFIX.to_string());
+            } else {
+                stages.push(self.cipher.clone());
+            }
+            return self.seal(&stages.join(","), reader, writer);
+        }
+        if self.decrypt {
+            // Envelopes say how to decrypt themselves, whatever the other flags say.
+            let mut head = Vec::new();
+            (&mut reader)
+                .take(ENVELOPE.len_utf8() as u64)
+                .read_to_end(&mut head)?;
+            let sealed = head == ENVELOPE.to_string().as_bytes();
+            let reader = Cursor::new(head).chain(reader);
+            if sealed {
+                return self.open(reader, writer);
+            }
+            return self.write_with_cipher(reader, writer);
+        }
+        self.write_with_cipher(reader, writer)
+    }
+
+    fn write_with_cipher<R, W>(&self, mut reader: R, mut writer: W) -> Result<()>
     where
         R: Read,
         W: Write + Finish,
     {
-        match self.cipher {
-            CipherType::Standard => self.cipher(reader, &mut writer, Standard {})?,
-            CipherType::Extended => self.cipher(reader, &mut writer, Extended {})?,
-            CipherType::Simple => self.cipher_v2(reader, &mut writer, Simple {})?,
+        let needs_key = self.tag || Registry::new().needs_key(&self.cipher);
+        let key = self.unlock(needs_key, self.encrypt)?;
+        let registry = self.registry(&key)?;
+        let cipher = registry.get(&self.cipher)?;
+        let cipher: Box<dyn DynCipher> = match self.mode {
+            Mode::Plain => Box::new(cipher),
+            Mode::Positional => Box::new(Positional::new(cipher)),
+            Mode::Chained => match self.seed {
+                Some(seed) => {
+                    let [b0, b1, ..] = sha256(&seed.to_be_bytes());
+                    Box::new(Chained::with_iv(cipher, (b0, b1))?)
+                }
+                None => Box::new(Chained::new(cipher)?),
+            },
+            Mode::Salted => match self.seed {
+                Some(seed) => Box::new(Salted::with_seed(cipher, seed)),
+                None => Box::new(Salted::new(cipher)),
+            },
+        };
+        let padded;
+        let cipher: &dyn DynCipher = match self.pad {
+            Some(padding) => {
+                padded = Padded::new(&*cipher, padding);
+                &padded
+            }
+            None => &*cipher,
+        };
+        let tagged;
+        let cipher: &dyn DynCipher = match (self.tag, &key) {
+            (true, Some(key)) => {
+                tagged = Tagged::new(cipher, key);
+                &tagged
+            }
+            (true, None) => return Err(Error::msg("tagging needs a key")),
+            (false, _) => cipher,
+        };
+        if self.encrypt {
+            cipher.encrypt(&mut reader, &mut writer)?;
+        } else {
+            cipher.decrypt(&mut reader, &mut writer)?;
         }
 
         writer.flush()?;
         writer.finish()
     }
 
-    fn cipher<R, W, C, const N: usize>(&self, reader: R, writer: W, cipher: C) -> Result<()>
+    fn seal<R, W>(&self, spec: &str, mut reader: R, mut writer: W) -> Result<()>
     where
         R: Read,
-        W: Write,
-        C: Cipher<N>,
+        W: Write + Finish,
     {
-        if self.encrypt {
-            cipher.encrypt(reader, writer)
-        } else {
-            cipher.decrypt(reader, writer)
+        let unkeyed = Registry::new();
+        let needs_key = Pipeline::stage_names(spec).any(|stage| unkeyed.needs_key(stage));
+        let key = self.unlock(needs_key, true)?;
+        let registry = self.registry(&key)?;
+        let pipeline = Pipeline::parse(spec, &registry)?;
+        let mut plaintext = Vec::new();
+        reader.read_to_end(&mut plaintext)?;
+        writer.write_all(pipeline.encrypt(&plaintext)?.as_bytes())?;
+        for summary in pipeline.summaries() {
+            eprintln!("{}", summary);
         }
+        writer.flush()?;
+        writer.finish()
     }
-    fn cipher_v2<R, W, C>(&self, reader: R, writer: W, cipher: C) -> Result<()>
+
+    fn open<R, W>(&self, mut reader: R, mut writer: W) -> Result<()>
     where
         R: Read,
-        W: Write,
-        C: CipherV2,
+        W: Write + Finish,
     {
-        if self.encrypt {
-            cipher.encrypt(reader, writer)
-        } else {
-            cipher.decrypt(reader, writer)
+        let mut envelope = String::new();
+        reader.read_to_string(&mut envelope)?;
+        let envelope = trim_newline(&envelope);
+        let (spec, _) = Pipeline::open(envelope)?;
+        self.report(format_args!("opening an envelope made with {}", spec));
+        let unkeyed = Registry::new();
+        let needs_key = Pipeline::stage_names(&spec).any(|stage| unkeyed.needs_key(stage));
+        let key = self.unlock(needs_key, false)?;
+        writer.write_all(&Pipeline::decrypt(envelope, &self.registry(&key)?)?)?;
+        writer.flush()?;
+        writer.finish()
+    }
+
+    fn transcode<R, W>(&self, from: &str, to: &str, mut reader: R, mut writer: W) -> Result<()>
+    where
+        R: Read,
+        W: Write + Finish,
+    {
+        let unkeyed = Registry::new();
+        let needs_key = unkeyed.needs_key(from) || unkeyed.needs_key(to);
+        let key = self.unlock(needs_key, unkeyed.needs_key(to))?;
+        let registry = self.registry(&key)?;
+        let from = registry.get(from)?;
+        let to = registry.get(to)?;
+        transcode(from, to, &mut reader, &mut writer)?;
+        writer.finish()
+    }
+
+    /// The ciphers we can use, including those needing the key and any custom alphabet.
+    fn registry(&self, key: &Option<Secret>) -> Result<Registry> {
+        let mut registry = match key {
+            Some(key) => Registry::with_key(key),
+            None => Registry::new(),
+        };
+        if let Some(path) = &self.alphabet_file {
+            let mut contents = String::new();
+            from_file(path)?.read_to_string(&mut contents)?;
+            let alphabet = if path.extension().is_some_and(|ext| ext == "toml") {
+                CustomAlphabet::from_toml(&contents)
+            } else {
+                CustomAlphabet::from_text(&contents)
+            }
+            .map_err(|err| Error::msg(format!("{}: {}", path.display(), err)))?;
+            self.report(format_args!("using {}", alphabet.info().name));
+            registry.register(Box::new(alphabet));
+        }
+        Ok(registry)
+    }
+
+    fn unlock(&self, needed: bool, confirm: bool) -> Result<Option<Secret>> {
+        let key = self.key(needed, confirm)?;
+        Ok(key.map(|(key, source)| {
+            self.report(format_args!("using the key from {}", source));
+            key
+        }))
+    }
+
+    /// Finds the key from, in order, --key, --key-file and UNICIPHER_KEY, and asks for one
+    /// on the terminal if none of those are set but one is needed.
+    fn key(&self, needed: bool, confirm: bool) -> Result<Option<(Secret, KeySource)>> {
+        if let Some(key) = &self.passphrase {
+            return Ok(Some((Secret::new(key.to_vec()), KeySource::Argument)));
+        }
+        if let Some(key_file) = &self.key_file {
+            let source = KeySource::File(key_file.clone());
+            return Ok(Some((passphrase::from_file(key_file)?, source)));
+        }
+        if let Some(key) = std::env::var_os(KEY_VAR) {
+            let key = key.into_encoded_bytes().into();
+            return Ok(Some((key, KeySource::Env(KEY_VAR))));
+        }
+        if needed {
+            let key = passphrase::prompt(confirm)?;
+            return Ok(Some((key, KeySource::Prompt)));
         }
+        Ok(None)
     }
 }
 
+fn list(registry: &Registry) -> Result<()> {
+    let mut writer = to_stdout();
+    writeln!(
+        writer,
+        "{:<4}{:<17}{:<14}{:<8}{:<26}DESCRIPTION",
+        "ID", "NAME", "EXPANSION", "BINARY", "OUTPUT RANGES"
+    )?;
+    for info in registry.infos() {
+        let ranges: Vec<String> = info
+            .output_ranges
+            .iter()
+            .map(|range| format!("U+{:04X}-U+{:04X}", range.start(), range.end()))
+            .collect();
+        let binary_safe = if info.binary_safe { "yes" } else { "no" };
+        for (i, range) in ranges.iter().enumerate() {
+            if i == 0 {
+                writeln!(
+                    writer,
+                    "{:<4}{:<17}{:<14}{:<8}{:<26}{}",
+                    info.id,
+                    info.name,
+                    info.expansion.to_string(),
+                    binary_safe,
+                    range,
+                    info.description
+                )?;
+            } else {
+                writeln!(writer, "{:<43}{}", "", range)?;
+            }
+        }
+    }
+    Ok(writer.flush()?)
+}
+
+fn crack<R: Read>(mut reader: R) -> Result<()> {
+    let mut ciphertext = String::new();
+    reader.read_to_string(&mut ciphertext)?;
+    let guess = unicipher::crack::crack(trim_newline(&ciphertext));
+    let mut writer = to_stdout();
+    let cipher = guess.cipher.map_or("unknown", |info| info.name);
+    if guess.solved {
+        writeln!(writer, "cipher:     {}, solved as a substitution", cipher)?;
+    } else {
+        writeln!(writer, "cipher:     {}", cipher)?;
+    }
+    writeln!(writer, "confidence: {:.0}%", guess.confidence * 100.0)?;
+    writeln!(writer)?;
+    writer.write_all(&guess.plaintext)?;
+    writer.finish()
+}
+
+/// Drops a trailing newline, most likely left by our own output, or an editor.
+fn trim_newline(text: &str) -> &str {
+    text.strip_suffix('\n')
+        .map(|stripped| stripped.strip_suffix('\r').unwrap_or(stripped))
+        .unwrap_or(text)
+}
+
 fn from_stdin() -> BufReader<Stdin> {
     BufReader::new(stdin())
 }
@@ -0,0 +1,98 @@
+use crate::cipherv2::{BytePair, BytePairs, Chars, CipherV2};
+use crate::crypto::{random_seed, KeyStream};
+use crate::info::CipherInfo;
+use crate::registry::DynCipher;
+use anyhow::{Error, Result};
+use std::io::{Read, Write};
+
+/**
+ * Chained is a CBC-like mode over any CipherV2. Before a pair is encrypted it's XORed with
+ * the previous character of ciphertext, so every character depends on everything before it.
+ * The first character of the output is a header carrying the initial value. A fresh random
+ * initial value for every message means identical messages, or messages that share a prefix,
+ * no longer encrypt the same way.
+ */
+pub struct Chained<C> {
+    cipher: C,
+    iv: Option<BytePair>,
+}
+
+impl<C: DynCipher> Chained<C> {
+    /// Chains `cipher` with a new random initial value for every message.
+    pub fn new(cipher: C) -> Result<Self> {
+        if cipher.as_cipher_v2().is_none() {
+            return Err(Error::msg(format!(
+                "chained mode needs a byte pair cipher, which {} isn't",
+                cipher.info().name
+            )));
+        }
+        Ok(Chained { cipher, iv: None })
+    }
+
+    /// Chains `cipher` using the same initial value every time.
+    pub fn with_iv(cipher: C, iv: BytePair) -> Result<Self> {
+        let mut chained = Chained::new(cipher)?;
+        chained.iv = Some(iv);
+        Ok(chained)
+    }
+
+    fn pairs(&self) -> &dyn CipherV2 {
+        // checked when we were created.
+        self.cipher.as_cipher_v2().unwrap()
+    }
+
+    fn feedback(&self, previous: char) -> BytePair {
+        let [_, b2, b1, b0] = (previous as u32).to_be_bytes();
+        mask((b2 ^ b1, b0), self.pairs().pair_mask())
+    }
+}
+
+impl<C: DynCipher> DynCipher for Chained<C> {
+    fn info(&self) -> &'static CipherInfo {
+        self.cipher.info()
+    }
+
+    fn encrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+        let pairs = self.pairs();
+        let iv = self.iv.unwrap_or_else(|| {
+            let [b0, b1, ..] = KeyStream::new(random_seed()).next_u32().to_be_bytes();
+            (b0, b1)
+        });
+        let mut previous = pairs.encrypt_char_pair(mask(iv, pairs.pair_mask()));
+        let mut buf = [0, 0, 0, 0];
+        writer.write_all(previous.encode_utf8(&mut buf).as_bytes())?;
+        for byte_pair in BytePairs::new(reader) {
+            let chained = xor(byte_pair?, self.feedback(previous));
+            previous = pairs.encrypt_char_pair(chained);
+            writer.write_all(previous.encode_utf8(&mut buf).as_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn decrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+        let pairs = self.pairs();
+        let mut chars = Chars::new(reader);
+        let mut previous = match chars.next() {
+            Some(header) => header?,
+            None => return Ok(()),
+        };
+        for encrypted in chars {
+            let encrypted = encrypted?;
+            let chained = pairs.decrypt_char_pair(encrypted);
+            match xor(chained, self.feedback(previous)) {
+                (c0, 0) => writer.write_all(&[c0]),
+                (c0, c1) => writer.write_all(&[c0, c1]),
+            }?;
+            previous = encrypted;
+        }
+        Ok(())
+    }
+}
+
+fn xor(a: BytePair, b: BytePair) -> BytePair {
+    (a.0 ^ b.0, a.1 ^ b.1)
+}
+
+fn mask(pair: BytePair, mask: BytePair) -> BytePair {
+    (pair.0 & mask.0, pair.1 & mask.1)
+}
@@ -0,0 +1,115 @@
+use crate::cipherv2::Chars;
+use std::io::{self, ErrorKind, Read, Write};
+use std::str::from_utf8;
+
+/**
+ * MapWriter decodes the utf-8 written to it, passes every char through a function and writes
+ * the result on to the wrapped writer. It lets us post-process whatever a cipher writes one
+ * char at a time, without buffering the whole output.
+ */
+pub struct MapWriter<W, F> {
+    writer: W,
+    map: F,
+    pending: [u8; 4],
+    pending_len: usize,
+}
+
+impl<W: Write, F: FnMut(char) -> char> MapWriter<W, F> {
+    pub fn new(writer: W, map: F) -> Self {
+        MapWriter {
+            writer,
+            map,
+            pending: [0; 4],
+            pending_len: 0,
+        }
+    }
+
+    /// Flushes the wrapped writer, failing if we were left holding part of a character.
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.pending_len > 0 {
+            return Err(invalid_data("output ended part way through a character"));
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn push(&mut self, byte: u8) -> io::Result<()> {
+        self.pending[self.pending_len] = byte;
+        self.pending_len += 1;
+        let char_len = match self.pending[0] {
+            0b0000_0000..=0b0111_1111 => 1,
+            0b1100_0000..=0b1101_1111 => 2,
+            0b1110_0000..=0b1110_1111 => 3,
+            0b1111_0000..=0b1111_0111 => 4,
+            _ => return Err(invalid_data("invalid first byte")),
+        };
+        if self.pending_len < char_len {
+            return Ok(());
+        }
+
+        let decoded = from_utf8(&self.pending[..char_len]).map_err(invalid_data)?;
+        let mapped = (self.map)(decoded.chars().next().unwrap());
+        self.pending_len = 0;
+        let mut buf = [0; 4];
+        self.writer
+            .write_all(mapped.encode_utf8(&mut buf).as_bytes())
+    }
+}
+
+impl<W: Write, F: FnMut(char) -> char> Write for MapWriter<W, F> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for byte in buf {
+            self.push(*byte)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/**
+ * MapReader is the reading half of MapWriter. It reads chars from the wrapped reader, passes
+ * each one through a function and hands out the utf-8 of the result.
+ */
+pub struct MapReader<R: Read, F> {
+    chars: Chars<R>,
+    map: F,
+    buf: [u8; 4],
+    pos: usize,
+    len: usize,
+}
+
+impl<R: Read, F: FnMut(char) -> char> MapReader<R, F> {
+    pub fn new(reader: R, map: F) -> Self {
+        MapReader {
+            chars: Chars::new(reader),
+            map,
+            buf: [0; 4],
+            pos: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<R: Read, F: FnMut(char) -> char> Read for MapReader<R, F> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.len {
+            let c = match self.chars.next() {
+                None => return Ok(0),
+                Some(c) => c.map_err(invalid_data)?,
+            };
+            self.len = (self.map)(c).encode_utf8(&mut self.buf).len();
+            self.pos = 0;
+        }
+        let n = out.len().min(self.len - self.pos);
+        out[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+fn invalid_data<E: ToString>(error: E) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, error.to_string())
+}
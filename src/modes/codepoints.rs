@@ -0,0 +1,81 @@
+use std::ops::RangeInclusive;
+
+/**
+ * CodePoints numbers the characters of a set of code point ranges, so that we can move around
+ * inside a cipher's output alphabet without ever stepping outside of it.
+ */
+pub struct CodePoints {
+    ranges: &'static [RangeInclusive<u32>],
+    len: u32,
+}
+
+impl CodePoints {
+    pub fn new(ranges: &'static [RangeInclusive<u32>]) -> Self {
+        for range in ranges {
+            assert!(
+                char::from_u32(*range.start()).is_some() && char::from_u32(*range.end()).is_some(),
+                "range {:X?} isn't made of valid chars",
+                range
+            );
+            assert!(
+                *range.start() > 0xDFFF || *range.end() < 0xD800,
+                "range {:X?} crosses the surrogates",
+                range
+            );
+        }
+        let len = ranges
+            .iter()
+            .map(|range| range.end() - range.start() + 1)
+            .sum();
+        CodePoints { ranges, len }
+    }
+
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn index_of(&self, c: char) -> Option<u32> {
+        let code_point = c as u32;
+        let mut offset = 0;
+        for range in self.ranges {
+            if range.contains(&code_point) {
+                return Some(offset + code_point - range.start());
+            }
+            offset += range.end() - range.start() + 1;
+        }
+        None
+    }
+
+    pub fn char_at(&self, mut index: u32) -> char {
+        for range in self.ranges {
+            let len = range.end() - range.start() + 1;
+            if index < len {
+                // the ranges were checked to hold only valid chars in new.
+                return char::from_u32(range.start() + index).unwrap();
+            }
+            index -= len;
+        }
+        panic!("index out of range");
+    }
+
+    /// Moves `c` forward by `shift` places, wrapping around at the end of the alphabet.
+    /// Characters outside of the alphabet are left alone.
+    pub fn rotate(&self, c: char, shift: u32) -> char {
+        match self.index_of(c) {
+            Some(index) => {
+                let rotated = (index as u64 + (shift % self.len) as u64) % self.len as u64;
+                self.char_at(rotated as u32)
+            }
+            None => c,
+        }
+    }
+
+    /// Undoes `rotate`.
+    pub fn unrotate(&self, c: char, shift: u32) -> char {
+        self.rotate(c, self.len - shift % self.len)
+    }
+}
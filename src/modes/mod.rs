@@ -0,0 +1,16 @@
+mod chained;
+mod charmap;
+mod codepoints;
+mod padded;
+mod positional;
+mod salted;
+mod tagged;
+#[cfg(test)]
+mod tests;
+
+pub use chained::Chained;
+pub use codepoints::CodePoints;
+pub use padded::{Padded, Padding};
+pub use positional::Positional;
+pub use salted::Salted;
+pub use tagged::{TagMismatch, Tagged};
@@ -0,0 +1,205 @@
+use crate::info::CipherInfo;
+use crate::registry::DynCipher;
+use anyhow::{Error, Result};
+use std::io::{self, Read, Write};
+use std::str::FromStr;
+
+// ISO/IEC 7816-4 style padding: a marker byte followed by as many filler bytes as needed.
+// The usual 0x80 and 0x00 don't survive the 7-bit ciphers or CipherV2's zero-padding, so we
+// use 0x01 and 0x02 instead. The marker means padding can always be told apart from the
+// plaintext, whatever the plaintext ends with.
+pub(crate) const MARKER: u8 = 0x01;
+pub(crate) const FILLER: u8 = 0x02;
+// Bigger blocks hide next to nothing more, and a typo could have us write out gigabytes.
+const MAX_BLOCK: usize = 64 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Padding {
+    /// Round up to the next multiple of this many bytes.
+    Block(usize),
+    /// Round up to the next power of two.
+    PowerOfTwo,
+}
+
+impl Padding {
+    /// The length `len` bytes of plaintext are padded out to. There's always at least one
+    /// byte of padding, for the marker. None if that's too long to count.
+    pub fn padded_len(&self, len: u64) -> Option<u64> {
+        match self {
+            Padding::Block(block) => {
+                let block = *block as u64;
+                (len / block + 1).checked_mul(block)
+            }
+            Padding::PowerOfTwo => len.checked_add(1)?.checked_next_power_of_two(),
+        }
+    }
+
+    pub fn pad(&self, bytes: &[u8]) -> Vec<u8> {
+        let mut padded = Vec::new();
+        // reading from and writing to memory can't fail.
+        PadReader::new(bytes, *self)
+            .read_to_end(&mut padded)
+            .unwrap();
+        padded
+    }
+
+    pub fn unpad(bytes: &[u8]) -> Result<Vec<u8>> {
+        let mut writer = UnpadWriter::new(Vec::new());
+        writer.write_all(bytes)?;
+        writer.finish()
+    }
+}
+
+impl FromStr for Padding {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.eq_ignore_ascii_case("pow2") {
+            return Ok(Padding::PowerOfTwo);
+        }
+        match s.parse() {
+            Ok(0) | Err(_) => Err(Error::msg(format!(
+                "invalid padding '{}', expected a block size or pow2",
+                s
+            ))),
+            Ok(block) if block > MAX_BLOCK => Err(Error::msg(format!(
+                "padding blocks can be at most {} bytes, not {}",
+                MAX_BLOCK, block
+            ))),
+            Ok(block) => Ok(Padding::Block(block)),
+        }
+    }
+}
+
+/**
+ * Padded pads the plaintext out to a fixed block size, or to a power of two, before handing
+ * it to a cipher so that the length of the ciphertext gives less away about the length of the
+ * plaintext. Decryption strips the padding off again.
+ */
+pub struct Padded<C> {
+    cipher: C,
+    padding: Padding,
+}
+
+impl<C: DynCipher> Padded<C> {
+    pub fn new(cipher: C, padding: Padding) -> Self {
+        Padded { cipher, padding }
+    }
+}
+
+impl<C: DynCipher> DynCipher for Padded<C> {
+    fn info(&self) -> &'static CipherInfo {
+        self.cipher.info()
+    }
+
+    fn encrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+        let mut reader = PadReader::new(reader, self.padding);
+        self.cipher.encrypt(&mut reader, writer)
+    }
+
+    fn decrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+        let mut writer = UnpadWriter::new(writer);
+        self.cipher.decrypt(reader, &mut writer)?;
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+/// Passes the wrapped reader through, then adds the padding once it runs dry.
+pub(crate) struct PadReader<R> {
+    reader: R,
+    padding: Padding,
+    read: u64,
+    // None until the wrapped reader runs dry, then the padding still to be handed out.
+    remaining: Option<u64>,
+}
+
+impl<R: Read> PadReader<R> {
+    pub(crate) fn new(reader: R, padding: Padding) -> Self {
+        PadReader {
+            reader,
+            padding,
+            read: 0,
+            remaining: None,
+        }
+    }
+}
+
+impl<R: Read> Read for PadReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let remaining = match self.remaining {
+            Some(remaining) => remaining,
+            None => {
+                let n = self.reader.read(buf)?;
+                if n > 0 {
+                    self.read += n as u64;
+                    return Ok(n);
+                }
+                let Some(padded_len) = self.padding.padded_len(self.read) else {
+                    return Err(io::Error::other("message is too long to pad"));
+                };
+                let padding = padded_len - self.read;
+                buf[0] = MARKER;
+                self.remaining = Some(padding - 1);
+                return Ok(1);
+            }
+        };
+        let n = buf.len().min(remaining.min(usize::MAX as u64) as usize);
+        buf[..n].fill(FILLER);
+        self.remaining = Some(remaining - n as u64);
+        Ok(n)
+    }
+}
+
+/// Holds back anything that might be padding until it's clear whether it is.
+pub(crate) struct UnpadWriter<W> {
+    writer: W,
+    // Either empty, or a marker followed only by filler.
+    pending: Vec<u8>,
+}
+
+impl<W: Write> UnpadWriter<W> {
+    pub(crate) fn new(writer: W) -> Self {
+        UnpadWriter {
+            writer,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Drops the padding, failing if there wasn't any.
+    pub(crate) fn finish(mut self) -> Result<W> {
+        if self.pending.first() != Some(&MARKER) {
+            return Err(Error::msg("message isn't padded"));
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl<W: Write> Write for UnpadWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for byte in buf {
+            match *byte {
+                FILLER if !self.pending.is_empty() => self.pending.push(FILLER),
+                MARKER => {
+                    self.writer.write_all(&self.pending)?;
+                    self.pending.clear();
+                    self.pending.push(MARKER);
+                }
+                byte => {
+                    self.writer.write_all(&self.pending)?;
+                    self.pending.clear();
+                    self.writer.write_all(&[byte])?;
+                }
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
@@ -0,0 +1,91 @@
+use super::charmap::{MapReader, MapWriter};
+use super::CodePoints;
+use crate::info::CipherInfo;
+use crate::registry::DynCipher;
+use anyhow::Result;
+use std::io::{Read, Write};
+
+/**
+ * Positional is a polyalphabetic mode that works over any cipher. Every character the cipher
+ * produces is rotated through the cipher's own output alphabet by an amount that depends on its
+ * position, Vigenère style. The same byte pair no longer turns into the same character every
+ * time it shows up, while the shift for any one position can still be worked out on its own,
+ * so each character can be decrypted without looking at the ones before it.
+ */
+pub struct Positional<C> {
+    cipher: C,
+    code_points: CodePoints,
+    seed: u64,
+}
+
+impl<C: DynCipher> Positional<C> {
+    pub fn new(cipher: C) -> Self {
+        Positional::with_seed(cipher, 0)
+    }
+
+    /// Uses a different sequence of shifts for each seed.
+    pub fn with_seed(cipher: C, seed: u64) -> Self {
+        let code_points = CodePoints::new(cipher.info().output_ranges);
+        Positional {
+            cipher,
+            code_points,
+            seed,
+        }
+    }
+
+    pub fn encrypt_char_at(&self, index: u64, c: char) -> char {
+        self.code_points.rotate(c, self.shift(index))
+    }
+
+    pub fn decrypt_char_at(&self, index: u64, c: char) -> char {
+        self.code_points.unrotate(c, self.shift(index))
+    }
+
+    /// Decrypts the single character found at `index` back into the bytes it came from.
+    pub fn decrypt_at(&self, index: u64, encrypted: char) -> Result<Vec<u8>> {
+        let unrotated = self.decrypt_char_at(index, encrypted);
+        self.cipher
+            .decrypt_to_vec(unrotated.encode_utf8(&mut [0; 4]))
+    }
+
+    fn shift(&self, index: u64) -> u32 {
+        (mix(self.seed, index) % self.code_points.len() as u64) as u32
+    }
+}
+
+impl<C: DynCipher> DynCipher for Positional<C> {
+    fn info(&self) -> &'static CipherInfo {
+        self.cipher.info()
+    }
+
+    fn encrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+        let mut index = 0;
+        let mut writer = MapWriter::new(writer, |c| {
+            let rotated = self.encrypt_char_at(index, c);
+            index += 1;
+            rotated
+        });
+        self.cipher.encrypt(reader, &mut writer)?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    fn decrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+        let mut index = 0;
+        let mut reader = MapReader::new(reader, |c| {
+            let unrotated = self.decrypt_char_at(index, c);
+            index += 1;
+            unrotated
+        });
+        self.cipher.decrypt(&mut reader, writer)
+    }
+}
+
+/// SplitMix64's finalizer over the seed and index, so neighbouring positions get unrelated
+/// shifts.
+fn mix(seed: u64, index: u64) -> u64 {
+    let mut z = seed.wrapping_add(index.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
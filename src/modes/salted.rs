@@ -0,0 +1,83 @@
+use super::{CodePoints, Positional};
+use crate::cipherv2::Chars;
+use crate::crypto::{random_seed, sha256, KeyStream, Sha256};
+use crate::info::CipherInfo;
+use crate::registry::DynCipher;
+use anyhow::{Error, Result};
+use std::io::{Read, Write};
+
+const SALT_LEN: usize = 4;
+
+/**
+ * Salted starts every message with a few random characters from the cipher's own alphabet and
+ * uses them to seed a Positional mode for the rest of the message. Encrypting the same text
+ * twice gives two different ciphertexts, while the salt tells decryption everything it needs.
+ */
+pub struct Salted<C> {
+    cipher: C,
+    code_points: CodePoints,
+    seed: Option<[u8; 32]>,
+}
+
+impl<C: DynCipher> Salted<C> {
+    /// Salts every message with fresh randomness.
+    pub fn new(cipher: C) -> Self {
+        let code_points = CodePoints::new(cipher.info().output_ranges);
+        Salted {
+            cipher,
+            code_points,
+            seed: None,
+        }
+    }
+
+    /// Picks salts from a fixed seed, so the output is reproducible.
+    pub fn with_seed(cipher: C, seed: u64) -> Self {
+        let mut salted = Salted::new(cipher);
+        salted.seed = Some(sha256(&seed.to_be_bytes()));
+        salted
+    }
+
+    fn positional(&self, salt: &[u32]) -> Positional<&C> {
+        let mut hasher = Sha256::new();
+        for index in salt {
+            hasher.update(&index.to_be_bytes());
+        }
+        let digest = hasher.finish();
+        let seed = u64::from_be_bytes(digest[..8].try_into().unwrap());
+        Positional::with_seed(&self.cipher, seed)
+    }
+}
+
+impl<C: DynCipher> DynCipher for Salted<C> {
+    fn info(&self) -> &'static CipherInfo {
+        self.cipher.info()
+    }
+
+    fn encrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+        let mut key_stream = KeyStream::new(self.seed.unwrap_or_else(random_seed));
+        let salt: Vec<u32> = (0..SALT_LEN)
+            .map(|_| key_stream.below(self.code_points.len()))
+            .collect();
+        let mut buf = [0, 0, 0, 0];
+        for index in &salt {
+            let salt_char = self.code_points.char_at(*index);
+            writer.write_all(salt_char.encode_utf8(&mut buf).as_bytes())?;
+        }
+        self.positional(&salt).encrypt(reader, writer)
+    }
+
+    fn decrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+        let mut salt = Vec::with_capacity(SALT_LEN);
+        for salt_char in Chars::new(&mut *reader).take(SALT_LEN) {
+            let index = self.code_points.index_of(salt_char?);
+            salt.push(index.ok_or_else(|| Error::msg("invalid salt"))?);
+        }
+        if salt.is_empty() {
+            return Ok(());
+        }
+        if salt.len() < SALT_LEN {
+            return Err(Error::msg("message is too short to hold its salt"));
+        }
+        self.positional(&salt).decrypt(reader, writer)
+    }
+}
@@ -0,0 +1,126 @@
+use crate::crypto::{hmac, tags_match, zeroize, Hmac};
+use crate::info::CipherInfo;
+use crate::registry::DynCipher;
+use anyhow::Result;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, ErrorKind, Read, Write};
+
+// We keep the first half of the HMAC, written out as hex so that every cipher can carry it.
+const TAG_LEN: usize = 16;
+const HEX_LEN: usize = TAG_LEN * 2;
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/**
+ * Tagged appends an HMAC-SHA256 of the plaintext, keyed with the passphrase, to the end of
+ * the message before encrypting it, so the tag comes out as more of the cipher's own
+ * characters. Decryption checks the tag and refuses to hand over anything that's been forged
+ * or altered.
+ */
+pub struct Tagged<C> {
+    cipher: C,
+    key: [u8; 32],
+}
+
+impl<C: DynCipher> Tagged<C> {
+    pub fn new(cipher: C, passphrase: &[u8]) -> Self {
+        // Keep the tag's key apart from anything else derived from the passphrase.
+        let key = hmac(passphrase, b"unicipher tag v1");
+        Tagged { cipher, key }
+    }
+
+    fn tag(&self, hmac: Hmac) -> [u8; HEX_LEN] {
+        let mut hex = [0; HEX_LEN];
+        for (i, byte) in hmac.finish()[..TAG_LEN].iter().enumerate() {
+            hex[i * 2] = HEX_DIGITS[(byte >> 4) as usize];
+            hex[i * 2 + 1] = HEX_DIGITS[(byte & 0x0f) as usize];
+        }
+        hex
+    }
+}
+
+impl<C> Drop for Tagged<C> {
+    fn drop(&mut self) {
+        zeroize(&mut self.key);
+    }
+}
+
+impl<C: DynCipher> DynCipher for Tagged<C> {
+    fn info(&self) -> &'static CipherInfo {
+        self.cipher.info()
+    }
+
+    fn encrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+        let mut reader = TagReader {
+            tagged: self,
+            reader,
+            hmac: Some(Hmac::new(&self.key)),
+            tag: Vec::new(),
+        };
+        self.cipher.encrypt(&mut reader, writer)
+    }
+
+    // Nothing is written until the whole message has been checked, so this holds the
+    // plaintext in memory rather than streaming it. A message that won't even decrypt has
+    // been tampered with just as surely as one whose tag is wrong, but a reader that fails
+    // says nothing about the message and is passed on as it is. Modes report text they can't
+    // decode as InvalidData, so that counts as tampering too.
+    fn decrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+        let mut decrypted = Vec::new();
+        if let Err(err) = self.cipher.decrypt(reader, &mut decrypted) {
+            return match err.downcast_ref::<io::Error>() {
+                Some(io_err) if io_err.kind() != ErrorKind::InvalidData => Err(err),
+                _ => Err(TagMismatch.into()),
+            };
+        }
+        if decrypted.len() < HEX_LEN {
+            return Err(TagMismatch.into());
+        }
+        let (plaintext, tag) = decrypted.split_at(decrypted.len() - HEX_LEN);
+        let mut hmac = Hmac::new(&self.key);
+        hmac.update(plaintext);
+        if !tags_match(&self.tag(hmac), tag) {
+            return Err(TagMismatch.into());
+        }
+        writer.write_all(plaintext)?;
+        Ok(())
+    }
+}
+
+/// Passes the wrapped reader through while hashing it, then adds the tag once it runs dry.
+struct TagReader<'a, C, R> {
+    tagged: &'a Tagged<C>,
+    reader: R,
+    // Taken once the wrapped reader runs dry and the tag has been worked out.
+    hmac: Option<Hmac>,
+    tag: Vec<u8>,
+}
+
+impl<C: DynCipher, R: Read> Read for TagReader<'_, C, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if let Some(hmac) = &mut self.hmac {
+            let n = self.reader.read(buf)?;
+            if n > 0 || buf.is_empty() {
+                hmac.update(&buf[..n]);
+                return Ok(n);
+            }
+            let hmac = self.hmac.take().unwrap();
+            self.tag = self.tagged.tag(hmac).to_vec();
+        }
+        let n = buf.len().min(self.tag.len());
+        buf[..n].copy_from_slice(&self.tag[..n]);
+        self.tag.drain(..n);
+        Ok(n)
+    }
+}
+
+/// The error decryption fails with when a message's tag doesn't match its contents.
+#[derive(Debug)]
+pub struct TagMismatch;
+
+impl Display for TagMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("message has been tampered with, or the key is wrong")
+    }
+}
+
+impl std::error::Error for TagMismatch {}
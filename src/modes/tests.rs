@@ -0,0 +1,322 @@
+use super::{Chained, CodePoints, Padded, Padding, Positional, Salted, TagMismatch, Tagged};
+use crate::info::Describe;
+use crate::registry::{DynCipher, Registry};
+use crate::stream::Words;
+use std::collections::HashSet;
+use std::io::ErrorKind;
+
+const TEST_CASES: [&str; 5] = [
+    "ad",
+    "bbb",
+    "hello there",
+    "!@#$%^&*()-_=+|~`,./<>?;':\"[]{}\\",
+    "120 mokneys on 40 barrels",
+];
+
+fn registry() -> Registry {
+    Registry::with_key(b"test key")
+}
+
+fn round_trips<C: DynCipher>(mode: C) {
+    for test_case in TEST_CASES {
+        let encrypted = mode.encrypt_to_string(test_case.as_bytes());
+        let decrypted = mode.decrypt_to_vec(&encrypted).expect("decryption failed");
+        let name = mode.info().name;
+        assert_eq!(test_case.as_bytes(), decrypted, "{} failed", name);
+    }
+}
+
+#[test]
+fn positional_round_trips() {
+    for cipher in registry().iter() {
+        round_trips(Positional::new(cipher));
+        round_trips(Positional::with_seed(cipher, 42));
+    }
+}
+
+#[test]
+fn positional_hides_repetition() {
+    let repeated = "a".repeat(64);
+    for cipher in registry().iter() {
+        if cipher.as_pair_cipher().is_some() {
+            let plain = cipher.encrypt_to_string(repeated.as_bytes());
+            assert_eq!(1, plain.chars().collect::<HashSet<_>>().len());
+        }
+
+        let positional = Positional::new(cipher).encrypt_to_string(repeated.as_bytes());
+        let distinct = positional.chars().collect::<HashSet<_>>().len();
+        assert!(
+            distinct > 16,
+            "{} only used {}",
+            cipher.info().name,
+            distinct
+        );
+    }
+}
+
+#[test]
+fn positional_stays_in_output_ranges() {
+    for cipher in registry().iter() {
+        let info = cipher.info();
+        let encrypted = Positional::new(cipher).encrypt_to_string("x".repeat(500).as_bytes());
+        for c in encrypted.chars() {
+            assert!(info.emits(c), "{} emitted {:?}", info.name, c);
+        }
+    }
+}
+
+#[test]
+fn positional_random_access() {
+    let plaintext = "a longer string than most of the others";
+    for cipher in registry().iter().filter(|c| c.as_pair_cipher().is_some()) {
+        let positional = Positional::new(cipher);
+        let encrypted = positional.encrypt_to_string(plaintext.as_bytes());
+        // walk backwards, so every character is decrypted without the ones before it.
+        let chars: Vec<char> = encrypted.chars().collect();
+        for (index, c) in chars.into_iter().enumerate().rev() {
+            let expected = plaintext.as_bytes().chunks(2).nth(index).unwrap();
+            let decrypted = positional.decrypt_at(index as u64, c).unwrap();
+            assert_eq!(expected, decrypted);
+        }
+    }
+}
+
+#[test]
+fn chained_round_trips() {
+    for cipher in registry().iter().filter(|c| c.as_cipher_v2().is_some()) {
+        round_trips(Chained::new(cipher).unwrap());
+        round_trips(Chained::with_iv(cipher, (0xFF, 0x00)).unwrap());
+    }
+}
+
+#[test]
+fn chained_needs_a_byte_pair_cipher() {
+    let registry = registry();
+    assert!(Chained::new(registry.get("standard").unwrap()).is_err());
+    assert!(Chained::new(registry.get("extended").unwrap()).is_err());
+    assert!(Chained::new(registry.get("simple").unwrap()).is_ok());
+}
+
+#[test]
+fn chained_iv_changes_shared_prefixes() {
+    for cipher in registry().iter().filter(|c| c.as_cipher_v2().is_some()) {
+        let first = Chained::with_iv(cipher, (1, 2)).unwrap();
+        let second = Chained::with_iv(cipher, (3, 4)).unwrap();
+        let a = first.encrypt_to_string(b"the same prefix, then one ending");
+        let b = second.encrypt_to_string(b"the same prefix, then another");
+        let shared = a.chars().zip(b.chars()).filter(|(a, b)| a == b).count();
+        assert!(shared < 4, "{} shared {} chars", cipher.info().name, shared);
+
+        let fresh: HashSet<String> = (0..5)
+            .map(|_| Chained::new(cipher).unwrap().encrypt_to_string(b"same"))
+            .collect();
+        assert!(fresh.len() > 1);
+    }
+}
+
+#[test]
+fn chained_hides_repetition() {
+    let repeated = "a".repeat(64);
+    for cipher in registry().iter().filter(|c| c.as_cipher_v2().is_some()) {
+        let chained = Chained::with_iv(cipher, (7, 7)).unwrap();
+        let encrypted = chained.encrypt_to_string(repeated.as_bytes());
+        let distinct = encrypted.chars().collect::<HashSet<_>>().len();
+        assert!(
+            distinct > 8,
+            "{} only used {}",
+            cipher.info().name,
+            distinct
+        );
+    }
+}
+
+#[test]
+fn salted_round_trips() {
+    for cipher in registry().iter() {
+        round_trips(Salted::new(cipher));
+        round_trips(Salted::with_seed(cipher, 1234));
+    }
+}
+
+#[test]
+fn salted_encryptions_differ() {
+    for cipher in registry().iter() {
+        let encrypted: HashSet<String> = (0..5)
+            .map(|_| Salted::new(cipher).encrypt_to_string(b"record 17"))
+            .collect();
+        assert_eq!(5, encrypted.len(), "{} repeated itself", cipher.info().name);
+    }
+}
+
+#[test]
+fn salted_seed_is_reproducible() {
+    for cipher in registry().iter() {
+        let a = Salted::with_seed(cipher, 99).encrypt_to_string(b"record 17");
+        let b = Salted::with_seed(cipher, 99).encrypt_to_string(b"record 17");
+        let c = Salted::with_seed(cipher, 100).encrypt_to_string(b"record 17");
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        for c in a.chars() {
+            assert!(cipher.info().emits(c));
+        }
+    }
+}
+
+#[test]
+fn salted_rejects_truncated_salt() {
+    let registry = registry();
+    let salted = Salted::with_seed(registry.get("simple").unwrap(), 5);
+    let encrypted = salted.encrypt_to_string(b"hi");
+    let truncated: String = encrypted.chars().take(2).collect();
+    assert!(salted.decrypt_to_vec(&truncated).is_err());
+    assert_eq!(Vec::<u8>::new(), salted.decrypt_to_vec("").unwrap());
+}
+
+#[test]
+fn padded_round_trips() {
+    for cipher in registry().iter() {
+        round_trips(Padded::new(cipher, Padding::Block(16)));
+        round_trips(Padded::new(cipher, Padding::Block(1)));
+        round_trips(Padded::new(cipher, Padding::PowerOfTwo));
+        round_trips(Padded::new(Salted::new(cipher), Padding::Block(7)));
+    }
+}
+
+#[test]
+fn padded_hides_length() {
+    // Every length short of a block pads out to the same block, so the cipher only ever sees that.
+    let padding = Padding::Block(32);
+    for cipher in registry().iter() {
+        let padded = Padded::new(cipher, padding);
+        for len in 0..32 {
+            let plaintext = vec![b'x'; len];
+            let block = padding.pad(&plaintext);
+            assert_eq!(32, block.len());
+            let name = cipher.info().name;
+            let expected = cipher.encrypt_to_string(&block);
+            assert_eq!(expected, padded.encrypt_to_string(&plaintext), "{}", name);
+        }
+    }
+}
+
+#[test]
+fn padded_words_hide_length() {
+    // Words vary in length, so it's the number of words that has to stay the same.
+    let registry = registry();
+    let padded = Padded::new(registry.by_id(Words::INFO.id).unwrap(), Padding::Block(32));
+    let counts: HashSet<usize> = (0..32)
+        .map(|len| padded.encrypt_to_string(&vec![b'x'; len]))
+        .map(|encrypted| encrypted.split(' ').count())
+        .collect();
+    assert_eq!(HashSet::from([32]), counts);
+}
+
+#[test]
+fn padding_is_unambiguous() {
+    // Plaintexts that end in what looks like padding still come back intact.
+    let tricky: [&[u8]; 5] = [b"", b"\x01", b"\x02\x02", b"ab\x01\x02", b"\x01\x01\x02"];
+    for padding in [Padding::Block(4), Padding::PowerOfTwo] {
+        for plaintext in tricky {
+            let padded = padding.pad(plaintext);
+            let expected = padding.padded_len(plaintext.len() as u64);
+            assert_eq!(expected, Some(padded.len() as u64));
+            assert_eq!(plaintext, Padding::unpad(&padded).unwrap());
+        }
+    }
+}
+
+#[test]
+fn padded_lengths() {
+    assert_eq!(Some(16), Padding::Block(16).padded_len(0));
+    assert_eq!(Some(16), Padding::Block(16).padded_len(15));
+    assert_eq!(Some(32), Padding::Block(16).padded_len(16));
+    assert_eq!(Some(1), Padding::PowerOfTwo.padded_len(0));
+    assert_eq!(Some(8), Padding::PowerOfTwo.padded_len(5));
+    assert_eq!(Some(16), Padding::PowerOfTwo.padded_len(8));
+    assert_eq!(None, Padding::Block(1 << 16).padded_len(u64::MAX - 1));
+    assert_eq!(None, Padding::PowerOfTwo.padded_len(1 << 63));
+}
+
+#[test]
+fn padding_from_str() {
+    assert_eq!(Padding::Block(64), "64".parse().unwrap());
+    assert_eq!(Padding::PowerOfTwo, "pow2".parse().unwrap());
+    assert!("0".parse::<Padding>().is_err());
+    assert!("lots".parse::<Padding>().is_err());
+    assert_eq!(Padding::Block(65536), "65536".parse().unwrap());
+    let err = "18446744073709551615".parse::<Padding>().unwrap_err();
+    assert!(err.to_string().contains("at most 65536"), "{}", err);
+}
+
+#[test]
+fn unpadding_rejects_unpadded_messages() {
+    assert!(Padding::unpad(b"").is_err());
+    assert!(Padding::unpad(b"no padding").is_err());
+    assert!(Padding::unpad(b"\x01\x02x").is_err());
+}
+
+#[test]
+fn tagged_round_trips() {
+    for cipher in registry().iter() {
+        round_trips(Tagged::new(cipher, b"tag key"));
+        round_trips(Tagged::new(Salted::new(cipher), b"tag key"));
+        let tagged = Tagged::new(cipher, b"tag key");
+        let encrypted = tagged.encrypt_to_string(b"");
+        assert_eq!(Vec::<u8>::new(), tagged.decrypt_to_vec(&encrypted).unwrap());
+        for c in encrypted.chars() {
+            assert!(cipher.info().emits(c));
+        }
+    }
+}
+
+#[test]
+fn tagged_rejects_altered_messages() {
+    for cipher in registry().iter() {
+        let code_points = CodePoints::new(cipher.info().output_ranges);
+        let tagged = Tagged::new(cipher, b"tag key");
+        let encrypted: Vec<char> = tagged.encrypt_to_string(b"pay bob 10").chars().collect();
+        for i in 0..encrypted.len() {
+            let mut altered = encrypted.clone();
+            altered[i] = code_points.rotate(altered[i], 1);
+            let altered: String = altered.into_iter().collect();
+            let err = tagged.decrypt_to_vec(&altered).unwrap_err();
+            assert!(err.is::<TagMismatch>(), "{}: {}", cipher.info().name, err);
+        }
+        let truncated: String = encrypted[1..].iter().collect();
+        assert!(tagged.decrypt_to_vec(&truncated).is_err());
+    }
+}
+
+#[test]
+fn tagged_passes_read_errors_on() {
+    struct Broken;
+    impl std::io::Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::new(ErrorKind::PermissionDenied, "no"))
+        }
+    }
+    for cipher in registry().iter() {
+        let tagged = Tagged::new(cipher, b"tag key");
+        let err = tagged.decrypt(&mut Broken, &mut Vec::new()).unwrap_err();
+        assert!(!err.is::<TagMismatch>(), "{}: {}", cipher.info().name, err);
+        let kind = err.downcast_ref::<std::io::Error>().map(|err| err.kind());
+        assert_eq!(Some(ErrorKind::PermissionDenied), kind);
+    }
+    // Modes that can't decode the text are still tampering.
+    let registry = registry();
+    let positional = Positional::new(registry.get("standard").unwrap());
+    let tagged = Tagged::new(positional, b"tag key");
+    let err = tagged.decrypt_to_vec("a").unwrap_err();
+    assert!(err.is::<TagMismatch>(), "{}", err);
+}
+
+#[test]
+fn tagged_rejects_the_wrong_key() {
+    let registry = registry();
+    let cipher = registry.get("keyed").unwrap();
+    let encrypted = Tagged::new(cipher, b"tag key").encrypt_to_string(b"hello there");
+    let err = Tagged::new(cipher, b"other key")
+        .decrypt_to_vec(&encrypted)
+        .unwrap_err();
+    assert!(err.is::<TagMismatch>());
+}
@@ -0,0 +1,151 @@
+use anyhow::{Error, Result};
+use std::fmt::{self, Display, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use unicipher::crypto::Secret;
+
+const TTY: &str = "/dev/tty";
+// Room enough for any reasonable passphrase, reserved up front so filling it never reallocates.
+const MAX_PASSPHRASE: usize = 1024;
+
+/// Where the key we ended up using came from.
+pub enum KeySource {
+    Argument,
+    File(PathBuf),
+    Env(&'static str),
+    Prompt,
+}
+
+impl Display for KeySource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            KeySource::Argument => write!(f, "--key"),
+            KeySource::File(path) => write!(f, "key file {}", path.display()),
+            KeySource::Env(var) => write!(f, "{}", var),
+            KeySource::Prompt => write!(f, "the terminal prompt"),
+        }
+    }
+}
+
+/// Reads a key file, refusing any that everyone on the machine could read too.
+pub fn from_file(path: &Path) -> Result<Secret> {
+    let mut file = File::open(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if file.metadata()?.permissions().mode() & 0o004 != 0 {
+            return Err(Error::msg(format!(
+                "key file {} is readable by everyone, restrict it with chmod o-r",
+                path.display()
+            )));
+        }
+    }
+    let len = file.metadata()?.len() as usize;
+    let mut key = Secret::new(Vec::with_capacity(len + 1));
+    file.read_to_end(key.as_mut_vec())?;
+    let key_vec = key.as_mut_vec();
+    if key_vec.ends_with(b"\n") {
+        key_vec.pop();
+        if key_vec.ends_with(b"\r") {
+            key_vec.pop();
+        }
+    }
+    Ok(key)
+}
+
+/// Asks for a passphrase on the terminal without echoing it. When encrypting we ask twice,
+/// as a typo would leave the message unreadable.
+pub fn prompt(confirm: bool) -> Result<Secret> {
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(TTY)
+        .map_err(|_| {
+            Error::msg("no terminal to ask for a passphrase on, use --key-file or UNICIPHER_KEY")
+        })?;
+    let _no_echo = NoEcho::new(&tty)?;
+    let passphrase = ask(&mut tty, "Passphrase: ")?;
+    if passphrase.is_empty() {
+        return Err(Error::msg("the passphrase can't be empty"));
+    }
+    if confirm && *ask(&mut tty, "Confirm passphrase: ")? != *passphrase {
+        return Err(Error::msg("the passphrases don't match"));
+    }
+    Ok(passphrase)
+}
+
+fn ask(tty: &mut File, question: &str) -> Result<Secret> {
+    tty.write_all(question.as_bytes())?;
+    tty.flush()?;
+    let mut answer = Secret::new(Vec::with_capacity(MAX_PASSPHRASE));
+    let mut byte = [0];
+    // One byte at a time, so no copy of the passphrase is left behind in a read buffer.
+    while tty.read(&mut byte)? == 1 && byte[0] != b'\n' {
+        if answer.len() == MAX_PASSPHRASE {
+            return Err(Error::msg("the passphrase is too long"));
+        }
+        answer.as_mut_vec().push(byte[0]);
+    }
+    if answer.ends_with(b"\r") {
+        answer.as_mut_vec().pop();
+    }
+    // The newline the user typed wasn't echoed either.
+    tty.write_all(b"\n")?;
+    Ok(answer)
+}
+
+/// Turns terminal echo off for as long as it's alive, using stty so we don't need bindings
+/// to termios.
+struct NoEcho {
+    tty: File,
+    saved: String,
+}
+
+impl NoEcho {
+    fn new(tty: &File) -> Result<Self> {
+        let tty = tty.try_clone()?;
+        let saved = stty(&tty, &["-g"])?;
+        stty(&tty, &["-echo"])?;
+        Ok(NoEcho { tty, saved })
+    }
+}
+
+impl Drop for NoEcho {
+    fn drop(&mut self) {
+        // Nothing more we can do if this fails, the user will have to run `stty echo`.
+        let _ = stty(&self.tty, &[self.saved.trim()]);
+    }
+}
+
+fn stty(tty: &File, args: &[&str]) -> Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(tty.try_clone()?)
+        .output()
+        .map_err(|err| Error::msg(format!("couldn't run stty: {}", err)))?;
+    if !output.status.success() {
+        return Err(Error::msg("couldn't turn off echo on the terminal"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::from_file;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn key_files() {
+        let path = std::env::temp_dir().join(format!("unicipher-key-{}", std::process::id()));
+        fs::write(&path, "correct horse\r\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+        assert_eq!(b"correct horse", &*from_file(&path).unwrap());
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(from_file(&path).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
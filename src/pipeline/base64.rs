@@ -0,0 +1,84 @@
+use anyhow::{Error, Result};
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const PAD: u8 = b'=';
+
+/// Standard base64 (RFC 4648), with padding.
+pub fn encode(bytes: &[u8]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| {
+            group | (*byte as u32) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - 6 * i) & 0b11_1111) as usize]);
+            } else {
+                encoded.push(PAD);
+            }
+        }
+    }
+    encoded
+}
+
+pub fn decode(encoded: &[u8]) -> Result<Vec<u8>> {
+    if !encoded.len().is_multiple_of(4) {
+        return Err(Error::msg("base64 length isn't a multiple of 4"));
+    }
+    let mut decoded = Vec::with_capacity(encoded.len() / 4 * 3);
+    for (n, chunk) in encoded.chunks(4).enumerate() {
+        let last = n == encoded.len() / 4 - 1;
+        let padding = chunk.iter().rev().take_while(|byte| **byte == PAD).count();
+        if padding > 2 || (padding > 0 && !last) {
+            return Err(Error::msg("misplaced base64 padding"));
+        }
+        let mut group = 0u32;
+        for (i, byte) in chunk[..4 - padding].iter().enumerate() {
+            let value = ALPHABET
+                .iter()
+                .position(|digit| digit == byte)
+                .ok_or_else(|| Error::msg(format!("invalid base64 digit {:?}", *byte as char)))?;
+            group |= (value as u32) << (18 - 6 * i);
+        }
+        decoded.extend(&group.to_be_bytes()[1..4 - padding]);
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode};
+
+    // From RFC 4648.
+    const VECTORS: [(&str, &str); 7] = [
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ];
+
+    #[test]
+    fn known_encodings() {
+        for (plain, encoded) in VECTORS {
+            assert_eq!(encoded.as_bytes(), encode(plain.as_bytes()));
+            assert_eq!(plain.as_bytes(), decode(encoded.as_bytes()).unwrap());
+        }
+    }
+
+    #[test]
+    fn every_byte_round_trips() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        assert_eq!(bytes, decode(&encode(&bytes)).unwrap());
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert!(decode(b"Zm9").is_err());
+        assert!(decode(b"Zm9!").is_err());
+        assert!(decode(b"Zg==Zm9v").is_err());
+        assert!(decode(b"Z===").is_err());
+    }
+}
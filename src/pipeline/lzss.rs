@@ -0,0 +1,172 @@
+use anyhow::{Error, Result};
+
+// Matches are two bytes: a 12 bit distance back into the window and a 4 bit length.
+const WINDOW: usize = 1 << 12;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = MIN_MATCH + 0b1111;
+const HASH_BITS: u32 = 12;
+// How far down the chain of earlier positions with the same hash we look for a match.
+const MAX_CHAIN: usize = 128;
+const NONE: usize = usize::MAX;
+
+/**
+ * LZSS compression. The output is a series of groups, each a flag byte followed by up to
+ * eight items. A set flag bit means the item is a literal byte, a clear one that it's a
+ * match: two bytes holding how far back the match starts, less one, and its length, less
+ * MIN_MATCH. Matches are found through hash chains over the last WINDOW bytes.
+ */
+pub fn compress(data: &[u8]) -> Vec<u8> {
+    let mut compressed = Vec::with_capacity(data.len() / 2);
+    let mut head = vec![NONE; 1 << HASH_BITS];
+    let mut prev = vec![NONE; data.len()];
+    let mut flags_at = 0;
+    let mut items = 8;
+
+    let mut pos = 0;
+    while pos < data.len() {
+        if items == 8 {
+            flags_at = compressed.len();
+            compressed.push(0);
+            items = 0;
+        }
+        let (distance, len) = longest_match(data, pos, &head, &prev);
+        if len >= MIN_MATCH {
+            let token = ((distance - 1) << 4 | (len - MIN_MATCH)) as u16;
+            compressed.extend(token.to_be_bytes());
+        } else {
+            compressed[flags_at] |= 1 << items;
+            compressed.push(data[pos]);
+        }
+        items += 1;
+        for _ in 0..len.max(1) {
+            if pos + MIN_MATCH <= data.len() {
+                let hash = hash(&data[pos..]);
+                prev[pos] = head[hash];
+                head[hash] = pos;
+            }
+            pos += 1;
+        }
+    }
+    compressed
+}
+
+pub fn decompress(compressed: &[u8]) -> Result<Vec<u8>> {
+    let mut data = Vec::with_capacity(compressed.len() * 2);
+    let mut bytes = compressed.iter().copied();
+    while let Some(flags) = bytes.next() {
+        for item in 0..8 {
+            let Some(first) = bytes.next() else {
+                break;
+            };
+            if flags & 1 << item != 0 {
+                data.push(first);
+                continue;
+            }
+            let second = bytes
+                .next()
+                .ok_or_else(|| Error::msg("compressed data ends part way through a match"))?;
+            let token = u16::from_be_bytes([first, second]) as usize;
+            let distance = (token >> 4) + 1;
+            let len = (token & 0b1111) + MIN_MATCH;
+            if distance > data.len() {
+                return Err(Error::msg("compressed data refers back past its start"));
+            }
+            // Byte by byte, as a match can overlap the bytes it produces.
+            let start = data.len() - distance;
+            for i in 0..len {
+                data.push(data[start + i]);
+            }
+        }
+    }
+    Ok(data)
+}
+
+fn longest_match(data: &[u8], pos: usize, head: &[usize], prev: &[usize]) -> (usize, usize) {
+    let mut best = (0, 0);
+    if pos + MIN_MATCH > data.len() {
+        return best;
+    }
+    let max_len = MAX_MATCH.min(data.len() - pos);
+    let mut candidate = head[hash(&data[pos..])];
+    for _ in 0..MAX_CHAIN {
+        if candidate == NONE || pos - candidate > WINDOW {
+            break;
+        }
+        let len = data[candidate..]
+            .iter()
+            .zip(&data[pos..pos + max_len])
+            .take_while(|(a, b)| a == b)
+            .count();
+        if len > best.1 {
+            best = (pos - candidate, len);
+            if len == max_len {
+                break;
+            }
+        }
+        candidate = prev[candidate];
+    }
+    best
+}
+
+fn hash(bytes: &[u8]) -> usize {
+    let key = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+    (key.wrapping_mul(2_654_435_761) >> (32 - HASH_BITS)) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compress, decompress};
+
+    fn round_trips(data: &[u8]) -> usize {
+        let compressed = compress(data);
+        assert_eq!(data, decompress(&compressed).unwrap());
+        compressed.len()
+    }
+
+    #[test]
+    fn small_inputs() {
+        for data in [&b""[..], b"a", b"ab", b"abc", b"aaaa", b"abcabcabc"] {
+            round_trips(data);
+        }
+    }
+
+    #[test]
+    fn repetitive_input_shrinks() {
+        let text = "the cat sat on the mat, the cat sat on the hat. ".repeat(50);
+        // A match covers at most 18 bytes, so about 8:1 is as good as it gets.
+        assert!(round_trips(text.as_bytes()) < text.len() / 7);
+        let zeros = vec![0; 100_000];
+        assert!(round_trips(&zeros) < 15_000);
+    }
+
+    #[test]
+    fn incompressible_input() {
+        let mut state = 1u32;
+        let noise: Vec<u8> = (0..10_000)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+        // Every literal costs an extra bit.
+        assert!(round_trips(&noise) <= noise.len() * 9 / 8 + 1);
+    }
+
+    #[test]
+    fn matches_beyond_the_window() {
+        let mut data: Vec<u8> = (0..=255).collect();
+        data.extend((0..5000u32).map(|i| (i * 7 % 251) as u8));
+        data.extend(0..=255);
+        round_trips(&data);
+    }
+
+    #[test]
+    fn rejects_corrupt_data() {
+        // A match with nothing before it.
+        assert!(decompress(&[0b0000_0000, 0x00, 0x00]).is_err());
+        // A match cut off half way.
+        assert!(decompress(&[0b0000_0001, b'a', 0x00]).is_err());
+    }
+}
@@ -0,0 +1,152 @@
+mod base64;
+mod lzss;
+mod stages;
+#[cfg(test)]
+mod tests;
+
+use crate::cipher::{Cipher, Standard};
+use crate::registry::Registry;
+use crate::stream::{CUSTOM_ID, CUSTOM_PREFIX};
+use anyhow::{Error, Result};
+
+pub use stages::{CipherStage, CompressStage, PadStage};
+
+/// Starts and ends the header of an envelope. It's a noncharacter, which none of our ciphers
+/// ever emit, so an envelope can't be mistaken for plain ciphertext.
+pub const ENVELOPE: char = '\u{FDD0}';
+
+/**
+ * Transform is a single reversible stage of a Pipeline, such as padding, or a cipher.
+ */
+pub trait Transform {
+    /// The stage as it's written in a pipeline, `pad:64` say.
+    fn spec(&self) -> String;
+    fn forward(&self, data: Vec<u8>) -> Result<Vec<u8>>;
+    fn reverse(&self, data: Vec<u8>) -> Result<Vec<u8>>;
+
+    /// What the last forward pass did, for stages worth telling the user about.
+    fn summary(&self) -> Option<String> {
+        None
+    }
+}
+
+/**
+ * Pipeline stacks Transforms, running them in order to encrypt and in reverse to decrypt.
+ * The last stage is always a cipher, so the output is text.
+ *
+ * Encrypting seals the output in an envelope: the pipeline's spec, itself run through
+ * Standard, between a pair of ENVELOPE chars, followed by the ciphertext. Decrypting reads
+ * the spec back out, so it doesn't need to be told how the message was made.
+ */
+pub struct Pipeline<'a> {
+    stages: Vec<Box<dyn Transform + 'a>>,
+}
+
+impl<'a> Pipeline<'a> {
+    /// Builds a pipeline from a comma separated list of stages, such as
+    /// `compress,pad:64,keyed,simple`.
+    /// Ciphers are looked up in the registry by name or ID.
+    pub fn parse(spec: &str, registry: &'a Registry) -> Result<Self> {
+        let stages = spec
+            .split(',')
+            .map(|stage| parse_stage(stage.trim(), registry))
+            .collect::<Result<Vec<_>>>()?;
+        let ends_in_cipher = spec
+            .rsplit(',')
+            .next()
+            .map(str::trim)
+            .is_some_and(|last| last == CUSTOM_PREFIX || registry.get(last).is_ok());
+        if !ends_in_cipher {
+            return Err(Error::msg(format!(
+                "pipeline '{}' has to end with a cipher",
+                spec
+            )));
+        }
+        Ok(Pipeline { stages })
+    }
+
+    /// The names of the stages of a pipeline spec, without their arguments.
+    pub fn stage_names(spec: &str) -> impl Iterator<Item = &str> {
+        spec.split(',')
+            .map(|stage| stage.split(':').next().unwrap_or(stage).trim())
+    }
+
+    pub fn spec(&self) -> String {
+        let specs: Vec<String> = self.stages.iter().map(|stage| stage.spec()).collect();
+        specs.join(",")
+    }
+
+    /// What each stage had to say about the last encryption, such as how well it compressed.
+    pub fn summaries(&self) -> impl Iterator<Item = String> + '_ {
+        self.stages.iter().filter_map(|stage| stage.summary())
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<String> {
+        let mut data = plaintext.to_vec();
+        for stage in &self.stages {
+            data = stage.forward(data)?;
+        }
+        let mut sealed = String::new();
+        sealed.push(ENVELOPE);
+        sealed.push_str(&Standard.encrypt_to_string(self.spec().as_bytes()));
+        sealed.push(ENVELOPE);
+        // The last stage is a cipher, so this is always valid utf8.
+        sealed.push_str(&String::from_utf8(data)?);
+        Ok(sealed)
+    }
+
+    /// Decrypts an envelope with the pipeline recorded in it.
+    pub fn decrypt(envelope: &str, registry: &Registry) -> Result<Vec<u8>> {
+        let (spec, body) = Pipeline::open(envelope)?;
+        let pipeline = Pipeline::parse(&spec, registry)?;
+        let mut data = body.as_bytes().to_vec();
+        for stage in pipeline.stages.iter().rev() {
+            data = stage.reverse(data)?;
+        }
+        Ok(data)
+    }
+
+    /// Splits an envelope into the pipeline spec and the ciphertext.
+    pub fn open(envelope: &str) -> Result<(String, &str)> {
+        let not_sealed = || Error::msg("message isn't sealed in an envelope");
+        let rest = envelope.strip_prefix(ENVELOPE).ok_or_else(not_sealed)?;
+        let (header, body) = rest.split_once(ENVELOPE).ok_or_else(not_sealed)?;
+        let spec = String::from_utf8(Standard.decrypt_to_vec(header)?)
+            .map_err(|_| Error::msg("envelope header is corrupt"))?;
+        Ok((spec, body))
+    }
+}
+
+fn parse_stage<'a>(stage: &str, registry: &'a Registry) -> Result<Box<dyn Transform + 'a>> {
+    match stage.split_once(':') {
+        Some(("pad", padding)) => Ok(Box::new(PadStage::new(padding.parse()?))),
+        None if stage == CUSTOM_PREFIX => match registry.by_id(CUSTOM_ID) {
+            Some(alphabet) => Ok(Box::new(CipherStage::new(alphabet))),
+            None => Err(Error::msg("no custom alphabet has been loaded")),
+        },
+        Some((CUSTOM_PREFIX, hash)) => match registry.by_id(CUSTOM_ID) {
+            Some(alphabet) if alphabet.info().name == stage => {
+                Ok(Box::new(CipherStage::new(alphabet)))
+            }
+            Some(alphabet) => Err(Error::msg(format!(
+                "message was made with alphabet {}, not the one given ({})",
+                hash,
+                &alphabet.info().name[CUSTOM_PREFIX.len() + 1..]
+            ))),
+            None => Err(Error::msg(format!(
+                "message was made with custom alphabet {}, which we haven't been given",
+                hash
+            ))),
+        },
+        None if stage == "compress" => Ok(Box::new(CompressStage::new())),
+        None if stage == "pad" => Err(Error::msg(
+            "the pad stage needs a size, such as pad:64 or pad:pow2",
+        )),
+        None => Ok(Box::new(CipherStage::new(registry.get(stage)?))),
+        // Ciphers can have a colon in their names too, script:hangul say.
+        Some(_) => match registry.get(stage) {
+            Ok(cipher) => Ok(Box::new(CipherStage::new(cipher))),
+            Err(_) => Err(Error::msg(format!("unknown pipeline stage '{}'", stage))),
+        },
+    }
+}
@@ -0,0 +1,175 @@
+use super::{base64, lzss, Transform};
+use crate::modes::Padding;
+use crate::registry::DynCipher;
+use anyhow::{Error, Result};
+use std::cell::Cell;
+use std::str::from_utf8;
+
+// Marks whether a cipher stage's input went in as is, or base64 armored.
+const RAW: u8 = b'r';
+const ARMORED: u8 = b'a';
+// Marks whether a compress stage stored its input, or compressed it, armoring the result
+// with base64 if the input was 7-bit.
+const STORED: u8 = b's';
+const COMPRESSED: u8 = b'c';
+const COMPRESSED_ARMORED: u8 = b'z';
+
+/**
+ * CipherStage runs the data through a cipher. Ciphers that aren't binary safe would mangle
+ * the output of earlier stages, so unless the data is all 7-bit and free of zeros, it's
+ * armored with base64 first. A leading byte records which it was.
+ */
+pub struct CipherStage<'a> {
+    cipher: &'a dyn DynCipher,
+}
+
+impl<'a> CipherStage<'a> {
+    pub fn new(cipher: &'a dyn DynCipher) -> Self {
+        CipherStage { cipher }
+    }
+}
+
+impl Transform for CipherStage<'_> {
+    fn spec(&self) -> String {
+        self.cipher.info().name.to_string()
+    }
+
+    fn forward(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+        if self.cipher.info().binary_safe {
+            return Ok(self.cipher.encrypt_to_string(&data).into_bytes());
+        }
+        let mut marked = Vec::with_capacity(data.len() + 1);
+        if seven_bit(&data) {
+            marked.push(RAW);
+            marked.extend(data);
+        } else {
+            marked.push(ARMORED);
+            marked.extend(base64::encode(&data));
+        }
+        Ok(self.cipher.encrypt_to_string(&marked).into_bytes())
+    }
+
+    fn reverse(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+        let encrypted = from_utf8(&data)?;
+        let decrypted = self.cipher.decrypt_to_vec(encrypted)?;
+        if self.cipher.info().binary_safe {
+            return Ok(decrypted);
+        }
+        match decrypted.split_first() {
+            Some((&RAW, data)) => Ok(data.to_vec()),
+            Some((&ARMORED, data)) => base64::decode(data),
+            _ => Err(Error::msg(format!(
+                "{} stage is missing its marker",
+                self.cipher.info().name
+            ))),
+        }
+    }
+}
+
+/// PadStage pads the data out to a block size or power of two, hiding its length.
+pub struct PadStage {
+    padding: Padding,
+}
+
+impl PadStage {
+    pub fn new(padding: Padding) -> Self {
+        PadStage { padding }
+    }
+}
+
+impl Transform for PadStage {
+    fn spec(&self) -> String {
+        match self.padding {
+            Padding::Block(block) => format!("pad:{}", block),
+            Padding::PowerOfTwo => "pad:pow2".to_string(),
+        }
+    }
+
+    fn forward(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+        Ok(self.padding.pad(&data))
+    }
+
+    fn reverse(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+        Padding::unpad(&data)
+    }
+}
+
+/**
+ * CompressStage shrinks the data with LZSS before it's encrypted. If that doesn't help, the
+ * data is stored as is, and a leading byte records which it was. When the input is 7-bit the
+ * compressed data is armored with base64 here, so it's weighed against the input as the
+ * cipher stage would see it.
+ */
+pub struct CompressStage {
+    // The sizes before and after the last forward pass.
+    sizes: Cell<Option<(usize, usize)>>,
+}
+
+impl CompressStage {
+    pub fn new() -> Self {
+        CompressStage {
+            sizes: Cell::new(None),
+        }
+    }
+}
+
+impl Default for CompressStage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transform for CompressStage {
+    fn spec(&self) -> String {
+        "compress".to_string()
+    }
+
+    fn forward(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+        let compressed = lzss::compress(&data);
+        let (marker, compressed) = if seven_bit(&data) {
+            (COMPRESSED_ARMORED, base64::encode(&compressed))
+        } else {
+            (COMPRESSED, compressed)
+        };
+        let mut marked = Vec::with_capacity(data.len().min(compressed.len()) + 1);
+        if compressed.len() < data.len() {
+            marked.push(marker);
+            marked.extend(compressed);
+        } else {
+            marked.push(STORED);
+            marked.extend(&data);
+        }
+        self.sizes.set(Some((data.len(), marked.len())));
+        Ok(marked)
+    }
+
+    fn reverse(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+        match data.split_first() {
+            Some((&STORED, data)) => Ok(data.to_vec()),
+            Some((&COMPRESSED, data)) => lzss::decompress(data),
+            Some((&COMPRESSED_ARMORED, data)) => lzss::decompress(&base64::decode(data)?),
+            _ => Err(Error::msg("compress stage is missing its marker")),
+        }
+    }
+
+    fn summary(&self) -> Option<String> {
+        let (before, after) = self.sizes.get()?;
+        if after > before {
+            return Some(format!(
+                "stored {} bytes uncompressed, compressing didn't help",
+                before
+            ));
+        }
+        Some(format!(
+            "compressed {} bytes to {} ({:.0}%)",
+            before,
+            after,
+            100.0 * after as f64 / before as f64
+        ))
+    }
+}
+
+// Whether data is all 7-bit and free of zeros, so any cipher can take it as is.
+fn seven_bit(data: &[u8]) -> bool {
+    data.iter().all(|byte| (0x01..=0x7f).contains(byte))
+}
@@ -0,0 +1,166 @@
+use super::{CompressStage, Pipeline, Transform, ENVELOPE};
+use crate::registry::Registry;
+use crate::stream::CustomAlphabet;
+
+const SPECS: [&str; 9] = [
+    "standard",
+    "pad:64,simple",
+    "keyed",
+    "keyed,simple",
+    "pad:pow2,standard,extended,simple",
+    "pad:16,keyed,pad:8,extended",
+    "2",
+    "compress,standard",
+    "compress,pad:32,keyed,compress,extended",
+];
+
+const TEST_CASES: [&[u8]; 4] = [b"", b"x", b"hello there", "caf\u{e9} \u{1F600}".as_bytes()];
+
+fn registry() -> Registry {
+    Registry::with_key(b"test key")
+}
+
+#[test]
+fn every_pipeline_round_trips() {
+    let registry = registry();
+    for spec in SPECS {
+        let pipeline = Pipeline::parse(spec, &registry).unwrap();
+        for test_case in TEST_CASES {
+            let sealed = pipeline.encrypt(test_case).unwrap();
+            let opened = Pipeline::decrypt(&sealed, &registry).unwrap();
+            assert_eq!(test_case, opened, "{} failed", spec);
+        }
+    }
+}
+
+#[test]
+fn binary_data_survives() {
+    let registry = registry();
+    let bytes: Vec<u8> = (0..=255).chain((0..=255).rev()).collect();
+    for spec in SPECS {
+        let pipeline = Pipeline::parse(spec, &registry).unwrap();
+        let sealed = pipeline.encrypt(&bytes).unwrap();
+        assert_eq!(bytes, Pipeline::decrypt(&sealed, &registry).unwrap());
+    }
+}
+
+#[test]
+fn envelope_records_the_pipeline() {
+    let registry = registry();
+    let pipeline = Pipeline::parse("pad:64, 4 ,simple", &registry).unwrap();
+    assert_eq!("pad:64,keyed,simple", pipeline.spec());
+    let sealed = pipeline.encrypt(b"hello").unwrap();
+    assert!(sealed.starts_with(ENVELOPE));
+    let (spec, body) = Pipeline::open(&sealed).unwrap();
+    assert_eq!("pad:64,keyed,simple", spec);
+    let info = registry.get("simple").unwrap().info();
+    assert!(body.chars().all(|c| info.emits(c)));
+}
+
+#[test]
+fn padding_hides_length() {
+    let registry = registry();
+    let pipeline = Pipeline::parse("pad:32,standard", &registry).unwrap();
+    let short = pipeline.encrypt(b"hi").unwrap();
+    let long = pipeline.encrypt(b"a good deal longer").unwrap();
+    assert_eq!(short.chars().count(), long.chars().count());
+}
+
+#[test]
+fn compression_shrinks_repetitive_messages() {
+    let registry = registry();
+    let plaintext = "all work and no play makes jack a dull boy\n".repeat(40);
+    let plain = Pipeline::parse("standard", &registry).unwrap();
+    let compressed = Pipeline::parse("compress,standard", &registry).unwrap();
+    let plain = plain.encrypt(plaintext.as_bytes()).unwrap();
+    let sealed = compressed.encrypt(plaintext.as_bytes()).unwrap();
+    assert!(sealed.chars().count() * 2 < plain.chars().count());
+    assert_eq!(
+        plaintext.as_bytes(),
+        Pipeline::decrypt(&sealed, &registry).unwrap()
+    );
+    let summary: Vec<String> = compressed.summaries().collect();
+    assert_eq!(1, summary.len());
+    assert!(summary[0].starts_with("compressed 1720 bytes to "));
+}
+
+#[test]
+fn compression_is_skipped_when_it_doesnt_help() {
+    let stage = CompressStage::new();
+    assert_eq!(None, stage.summary());
+    for data in [&b"short"[..], b"", &[0xde, 0xad, 0xbe, 0xef]] {
+        let stored = stage.forward(data.to_vec()).unwrap();
+        assert_eq!(data.len() + 1, stored.len());
+        assert_eq!(data, stage.reverse(stored).unwrap());
+        assert!(stage.summary().unwrap().starts_with("stored"));
+    }
+    // Text stays text, so a 7-bit cipher can take it without armoring.
+    let text = "abcabcabcabcabcabcabcabcabcabcabcabcabc".repeat(4);
+    let compressed = stage.forward(text.clone().into_bytes()).unwrap();
+    assert!(compressed.len() < text.len());
+    assert!(compressed.iter().all(|byte| (0x01..=0x7f).contains(byte)));
+    assert_eq!(text.as_bytes(), stage.reverse(compressed).unwrap());
+    assert!(stage.reverse(b"xyz".to_vec()).is_err());
+    assert!(stage.reverse(Vec::new()).is_err());
+}
+
+#[test]
+fn envelope_records_the_alphabet() {
+    let mut registry = registry();
+    let alphabet = CustomAlphabet::from_text("ABCDEFGHIJKLMNOPQRSTUVWXYZ").unwrap();
+    let name = alphabet.info().name;
+    registry.register(Box::new(alphabet));
+    let pipeline = Pipeline::parse("compress,alphabet", &registry).unwrap();
+    assert_eq!(format!("compress,{}", name), pipeline.spec());
+    let sealed = pipeline.encrypt(b"hello").unwrap();
+    assert_eq!(
+        b"hello",
+        &Pipeline::decrypt(&sealed, &registry).unwrap()[..]
+    );
+
+    let mut other = self::registry();
+    let alphabet = CustomAlphabet::from_text("ZYXWVUTSRQPONMLKJIHGFEDCBA").unwrap();
+    other.register(Box::new(alphabet));
+    let err = Pipeline::decrypt(&sealed, &other).unwrap_err();
+    assert!(err.to_string().contains("not the one given"), "{}", err);
+    let err = Pipeline::decrypt(&sealed, &self::registry()).unwrap_err();
+    assert!(err.to_string().contains("haven't been given"), "{}", err);
+    assert!(Pipeline::parse("alphabet", &self::registry()).is_err());
+}
+
+#[test]
+fn invalid_pipelines() {
+    let registry = registry();
+    for spec in [
+        "",
+        "pad:64",
+        "standard,pad:64",
+        "pad",
+        "pad:0,simple",
+        "pad:18446744073709551615,simple",
+        "rot13,simple",
+        "compress",
+        "compress:9,standard",
+    ] {
+        assert!(Pipeline::parse(spec, &registry).is_err(), "{} parsed", spec);
+    }
+    assert!(Pipeline::parse("keyed", &Registry::new()).is_err());
+}
+
+#[test]
+fn rejects_messages_without_an_envelope() {
+    let registry = registry();
+    let plain = registry
+        .get("standard")
+        .unwrap()
+        .encrypt_to_string(b"hello");
+    assert!(Pipeline::decrypt(&plain, &registry).is_err());
+    let unterminated = format!("{}{}", ENVELOPE, plain);
+    assert!(Pipeline::decrypt(&unterminated, &registry).is_err());
+}
+
+#[test]
+fn stage_names() {
+    let names: Vec<&str> = Pipeline::stage_names("pad:64, keyed,simple").collect();
+    assert_eq!(vec!["pad", "keyed", "simple"], names);
+}
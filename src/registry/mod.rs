@@ -0,0 +1,323 @@
+#[cfg(test)]
+mod tests;
+mod transcode;
+
+use crate::cipher::{self, Cipher, Extended, Private, Standard};
+use crate::cipherv2::{CipherV2, Keyed, Simple};
+use crate::info::{CipherInfo, Describe};
+use crate::stream::{
+    Base32768, Base65536, Braille, CustomAlphabet, Dense, RadixCipher, Script, Words,
+};
+use anyhow::{Error, Result};
+use std::io::{Read, Write};
+use std::str::from_utf8;
+
+pub use transcode::transcode;
+
+/**
+ * DynCipher is the object safe face of both Cipher<N> and CipherV2. It trades the generic
+ * readers and writers of those traits for trait objects so that ciphers can be boxed, stored
+ * in a Registry and picked at runtime.
+ */
+pub trait DynCipher {
+    fn info(&self) -> &'static CipherInfo;
+    fn encrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()>;
+    fn decrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()>;
+
+    /// The cipher as a plain byte pair to char mapping, if that's what it is.
+    fn as_cipher_v2(&self) -> Option<&dyn CipherV2> {
+        None
+    }
+
+    /// The cipher as one that works a char at a time, if it can.
+    fn as_pair_cipher(&self) -> Option<&dyn PairCipher> {
+        None
+    }
+
+    fn encrypt_to_string(&self, bytes: &[u8]) -> String {
+        let mut encrypted = Vec::new();
+        // we should always produce valid utf8, if not, that's a bug and we should panic.
+        self.encrypt(&mut &*bytes, &mut encrypted).unwrap();
+        String::from_utf8(encrypted).unwrap()
+    }
+
+    fn decrypt_to_vec(&self, encrypted: &str) -> Result<Vec<u8>> {
+        let mut decrypted = Vec::new();
+        self.decrypt(&mut encrypted.as_bytes(), &mut decrypted)?;
+        Ok(decrypted)
+    }
+}
+
+impl<C: DynCipher + ?Sized> DynCipher for &C {
+    fn info(&self) -> &'static CipherInfo {
+        (**self).info()
+    }
+
+    fn encrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+        (**self).encrypt(reader, writer)
+    }
+
+    fn decrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+        (**self).decrypt(reader, writer)
+    }
+
+    fn as_cipher_v2(&self) -> Option<&dyn CipherV2> {
+        (**self).as_cipher_v2()
+    }
+
+    fn as_pair_cipher(&self) -> Option<&dyn PairCipher> {
+        (**self).as_pair_cipher()
+    }
+
+    fn encrypt_to_string(&self, bytes: &[u8]) -> String {
+        (**self).encrypt_to_string(bytes)
+    }
+
+    fn decrypt_to_vec(&self, encrypted: &str) -> Result<Vec<u8>> {
+        (**self).decrypt_to_vec(encrypted)
+    }
+}
+
+/**
+ * PairCipher encrypts a single byte pair to a single char and back again. It's the common
+ * ground between Cipher<N> and CipherV2 that lets ciphertext be moved from one cipher to
+ * another a char at a time.
+ */
+pub trait PairCipher {
+    fn encrypt_pair(&self, pair: cipher::BytePair) -> char;
+    fn decrypt_char(&self, encrypted: char) -> Result<cipher::BytePair>;
+}
+
+macro_rules! dyn_cipher {
+    (Cipher<$n:literal>, $cipher:ty) => {
+        impl PairCipher for $cipher {
+            fn encrypt_pair(&self, pair: cipher::BytePair) -> char {
+                let encrypted = Cipher::<$n>::encrypt_char_pair(self, pair);
+                // we should always produce valid utf8, if not, that's a bug and we should panic.
+                from_utf8(&encrypted).unwrap().chars().next().unwrap()
+            }
+
+            fn decrypt_char(&self, encrypted: char) -> Result<cipher::BytePair> {
+                let mut buf = [0; $n];
+                if encrypted.len_utf8() != $n {
+                    return Err(cipher::wrong_char_width::<$n>(encrypted));
+                }
+                encrypted.encode_utf8(&mut buf);
+                Cipher::<$n>::check_char(self, buf)?;
+                Ok(Cipher::<$n>::decrypt_char_pair(self, buf))
+            }
+        }
+        dyn_cipher!(Cipher, $cipher, {});
+    };
+    (CipherV2, $cipher:ty) => {
+        impl PairCipher for $cipher {
+            fn encrypt_pair(&self, pair: cipher::BytePair) -> char {
+                CipherV2::encrypt_char_pair(self, (pair.0, pair.1.unwrap_or(0)))
+            }
+
+            fn decrypt_char(&self, encrypted: char) -> Result<cipher::BytePair> {
+                Ok(match CipherV2::decrypt_char_pair(self, encrypted) {
+                    (c0, 0) => (c0, None),
+                    (c0, c1) => (c0, Some(c1)),
+                })
+            }
+        }
+        dyn_cipher!(CipherV2, $cipher, {
+            fn as_cipher_v2(&self) -> Option<&dyn CipherV2> {
+                Some(self)
+            }
+        });
+    };
+    (Stream, $cipher:ty) => {
+        impl DynCipher for $cipher {
+            fn info(&self) -> &'static CipherInfo {
+                static INFO: CipherInfo = <$cipher as Describe>::INFO;
+                &INFO
+            }
+
+            fn encrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+                <$cipher>::encrypt(self, reader, writer)
+            }
+
+            fn decrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+                <$cipher>::decrypt(self, reader, writer)
+            }
+        }
+    };
+    ($cipher_trait:ident, $cipher:ty, { $($extra:tt)* }) => {
+        impl DynCipher for $cipher {
+            $($extra)*
+
+            fn as_pair_cipher(&self) -> Option<&dyn PairCipher> {
+                Some(self)
+            }
+
+            fn info(&self) -> &'static CipherInfo {
+                static INFO: CipherInfo = <$cipher as Describe>::INFO;
+                &INFO
+            }
+
+            fn encrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+                $cipher_trait::encrypt(self, reader, writer)
+            }
+
+            fn decrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+                $cipher_trait::decrypt(self, reader, writer)
+            }
+
+            fn encrypt_to_string(&self, bytes: &[u8]) -> String {
+                $cipher_trait::encrypt_to_string(self, bytes)
+            }
+
+            fn decrypt_to_vec(&self, encrypted: &str) -> Result<Vec<u8>> {
+                $cipher_trait::decrypt_to_vec(self, encrypted)
+            }
+        }
+    };
+}
+
+dyn_cipher!(Cipher<3>, Standard);
+dyn_cipher!(Cipher<4>, Extended);
+dyn_cipher!(Cipher<4>, Private);
+dyn_cipher!(CipherV2, Simple);
+dyn_cipher!(CipherV2, Keyed);
+dyn_cipher!(Stream, Dense);
+dyn_cipher!(Stream, Braille);
+dyn_cipher!(Stream, Base32768);
+dyn_cipher!(Stream, Base65536);
+dyn_cipher!(Stream, Words);
+
+macro_rules! radix_cipher {
+    ($cipher:ty) => {
+        impl DynCipher for $cipher {
+            fn info(&self) -> &'static CipherInfo {
+                <$cipher>::info(self)
+            }
+
+            fn encrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+                RadixCipher::encrypt(self, reader, writer)
+            }
+
+            fn decrypt(&self, reader: &mut dyn Read, writer: &mut dyn Write) -> Result<()> {
+                RadixCipher::decrypt(self, reader, writer)
+            }
+
+            fn encrypt_to_string(&self, bytes: &[u8]) -> String {
+                RadixCipher::encrypt_to_string(self, bytes)
+            }
+
+            fn decrypt_to_vec(&self, encrypted: &str) -> Result<Vec<u8>> {
+                RadixCipher::decrypt_to_vec(self, encrypted)
+            }
+        }
+    };
+}
+
+radix_cipher!(CustomAlphabet);
+radix_cipher!(Script);
+
+pub struct Registry {
+    ciphers: Vec<Box<dyn DynCipher>>,
+    // Ciphers we know about but can't build until we're given a key.
+    locked: Vec<&'static CipherInfo>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        let mut registry = Registry {
+            ciphers: Vec::new(),
+            locked: vec![&Keyed::INFO],
+        };
+        registry.register(Box::new(Standard));
+        registry.register(Box::new(Extended));
+        registry.register(Box::new(Simple));
+        registry.register(Box::new(Dense));
+        for script in Script::all() {
+            registry.register(Box::new(script));
+        }
+        registry.register(Box::new(Braille));
+        registry.register(Box::new(Private));
+        registry.register(Box::new(Base32768));
+        registry.register(Box::new(Base65536));
+        registry.register(Box::new(Words));
+        registry
+    }
+
+    /// A registry that also holds the ciphers which need a key.
+    pub fn with_key(key: &[u8]) -> Self {
+        let mut registry = Registry::new();
+        registry.register(Box::new(Keyed::new(key)));
+        registry
+    }
+
+    pub fn register(&mut self, cipher: Box<dyn DynCipher>) {
+        let info = cipher.info();
+        assert!(
+            self.by_name(info.name).is_none() && self.by_id(info.id).is_none(),
+            "cipher {} ({}) registered twice",
+            info.name,
+            info.id
+        );
+        self.locked.retain(|locked| locked.id != info.id);
+        self.ciphers.push(cipher);
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<&dyn DynCipher> {
+        self.iter()
+            .find(|cipher| cipher.info().name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn by_id(&self, id: u8) -> Option<&dyn DynCipher> {
+        self.iter().find(|cipher| cipher.info().id == id)
+    }
+
+    /// Looks a cipher up by its name, or by its numeric ID.
+    pub fn get(&self, name_or_id: &str) -> Result<&dyn DynCipher> {
+        let cipher = match name_or_id.parse() {
+            Ok(id) => self.by_id(id),
+            Err(_) => self.by_name(name_or_id),
+        };
+        if let Some(cipher) = cipher {
+            return Ok(cipher);
+        }
+        if let Some(locked) = self.locked(name_or_id) {
+            return Err(Error::msg(format!("cipher '{}' needs a key", locked.name)));
+        }
+        Err(Error::msg(format!(
+            "unknown cipher '{}', expected one of: {}",
+            name_or_id,
+            self.names().collect::<Vec<_>>().join(", ")
+        )))
+    }
+
+    /// Whether the named cipher can only be used once we've been given a key.
+    pub fn needs_key(&self, name_or_id: &str) -> bool {
+        self.locked(name_or_id).is_some()
+    }
+
+    fn locked(&self, name_or_id: &str) -> Option<&'static CipherInfo> {
+        self.locked.iter().copied().find(|info| {
+            info.name.eq_ignore_ascii_case(name_or_id) || info.id.to_string() == name_or_id
+        })
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.infos().map(|info| info.name)
+    }
+
+    /// Describes every cipher we know about, including the ones still waiting on a key.
+    pub fn infos(&self) -> impl Iterator<Item = &'static CipherInfo> + '_ {
+        let unlocked = self.iter().map(|cipher| cipher.info());
+        unlocked.chain(self.locked.iter().copied())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn DynCipher> {
+        self.ciphers.iter().map(|cipher| cipher.as_ref())
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Registry::new()
+    }
+}
@@ -0,0 +1,172 @@
+use super::{transcode, DynCipher, Registry};
+use crate::modes::Positional;
+use std::collections::HashSet;
+
+const TEST_CASES: [&str; 6] = [
+    "ad",
+    "bbb",
+    "x",
+    "hello there",
+    "!@#$%^&*()-_=+|~`,./<>?;':\"[]{}\\",
+    "120 mokneys on 40 barrels",
+];
+
+#[test]
+fn every_cipher_round_trips() {
+    for cipher in registry().iter() {
+        for test_case in TEST_CASES {
+            let encrypted = encrypt_string(cipher, test_case);
+            let decrypted = decrypt_string(cipher, &encrypted);
+            let name = cipher.info().name;
+            assert_eq!(test_case, decrypted, "{} failed to round trip", name);
+        }
+    }
+}
+
+#[test]
+fn string_api_matches_streams() {
+    for cipher in registry().iter() {
+        for test_case in TEST_CASES {
+            let encrypted = cipher.encrypt_to_string(test_case.as_bytes());
+            assert_eq!(encrypt_string(cipher, test_case), encrypted);
+            let decrypted = cipher.decrypt_to_vec(&encrypted).unwrap();
+            assert_eq!(test_case.as_bytes(), decrypted);
+        }
+    }
+}
+
+#[test]
+fn names_and_ids_are_unique() {
+    let registry = registry();
+    let names: HashSet<_> = registry.names().collect();
+    let ids: HashSet<_> = registry.iter().map(|cipher| cipher.info().id).collect();
+    assert_eq!(registry.iter().count(), names.len());
+    assert_eq!(registry.iter().count(), ids.len());
+}
+
+#[test]
+fn lookup_by_name_or_id() {
+    let registry = registry();
+    for cipher in registry.iter() {
+        let info = cipher.info();
+        assert_eq!(info.id, registry.get(info.name).unwrap().info().id);
+        assert_eq!(
+            info.id,
+            registry.get(&info.id.to_string()).unwrap().info().id
+        );
+    }
+    assert_eq!("standard", registry.get("Standard").unwrap().info().name);
+    assert!(registry.get("rot13").is_err());
+    assert!(registry.get("200").is_err());
+}
+
+#[test]
+fn output_stays_in_declared_ranges() {
+    for cipher in registry().iter() {
+        let info = cipher.info();
+        for c0 in 0..=u8::MAX {
+            for c1 in 0..=u8::MAX {
+                let encrypted = cipher.encrypt_to_string(&[c0, c1]);
+                let encrypted = encrypted + &cipher.encrypt_to_string(&[c1]);
+                for c in encrypted.chars() {
+                    assert!(info.emits(c), "{} emitted {:?}", info.name, c);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn keyed_ciphers_need_a_key() {
+    let registry = Registry::new();
+    assert!(registry.get("keyed").is_err());
+    assert!(registry.names().any(|name| name == "keyed"));
+    assert!(registry.infos().any(|info| info.name == "keyed"));
+    assert!(registry.needs_key("keyed"));
+    assert!(registry.needs_key("4"));
+    assert!(!registry.needs_key("standard"));
+    assert!(!self::registry().needs_key("keyed"));
+}
+
+#[test]
+fn transcodes_between_every_pair_of_ciphers() {
+    let registry = registry();
+    let pair_ciphers = || registry.iter().filter(|c| c.as_pair_cipher().is_some());
+    for from in pair_ciphers() {
+        for to in pair_ciphers() {
+            for test_case in TEST_CASES.into_iter().chain([""]) {
+                let encrypted = from.encrypt_to_string(test_case.as_bytes());
+                let mut transcoded = Vec::new();
+                transcode(from, to, &mut encrypted.as_bytes(), &mut transcoded)
+                    .expect("transcoding failed");
+                assert_eq!(
+                    to.encrypt_to_string(test_case.as_bytes()).as_bytes(),
+                    transcoded,
+                    "{} to {} failed",
+                    from.info().name,
+                    to.info().name
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn transcoding_needs_pair_ciphers() {
+    let registry = registry();
+    let standard = registry.get("standard").unwrap();
+    let positional = Positional::new(standard);
+    let encrypted = standard.encrypt_to_string(b"hello");
+    let mut transcoded = Vec::new();
+    assert!(transcode(
+        standard,
+        &positional,
+        &mut encrypted.as_bytes(),
+        &mut transcoded
+    )
+    .is_err());
+    assert!(transcode(
+        &positional,
+        standard,
+        &mut encrypted.as_bytes(),
+        &mut transcoded
+    )
+    .is_err());
+    let dense = registry.get("dense").unwrap();
+    let encrypted_dense = dense.encrypt_to_string(b"hello");
+    assert!(transcode(
+        dense,
+        standard,
+        &mut encrypted_dense.as_bytes(),
+        &mut transcoded
+    )
+    .is_err());
+    let mismatched = registry.get("extended").unwrap();
+    assert!(transcode(
+        mismatched,
+        standard,
+        &mut encrypted.as_bytes(),
+        &mut transcoded
+    )
+    .is_err());
+}
+
+fn registry() -> Registry {
+    Registry::with_key(b"test key")
+}
+
+fn encrypt_string(cipher: &dyn DynCipher, to_encrypt: &str) -> String {
+    let mut result = Vec::new();
+    cipher
+        .encrypt(&mut to_encrypt.as_bytes(), &mut result)
+        .expect("encryption failed");
+    String::from_utf8(result).expect("invalid utf-8")
+}
+
+fn decrypt_string(cipher: &dyn DynCipher, to_decrypt: &str) -> String {
+    let mut result = Vec::new();
+    cipher
+        .decrypt(&mut to_decrypt.as_bytes(), &mut result)
+        .expect("decryption failed");
+    String::from_utf8(result).expect("invalid utf-8")
+}
@@ -0,0 +1,70 @@
+use super::{DynCipher, PairCipher};
+use crate::cipherv2::Chars;
+use crate::crypto::zeroize;
+use anyhow::{Error, Result};
+use std::io::{Read, Write};
+
+/**
+ * transcode moves ciphertext from one cipher to another in a single streaming pass, each char
+ * decrypted and the bytes straight away encrypted again. No more than a byte pair of
+ * plaintext is held at any time, and it's zeroed once we're done, so the plaintext never
+ * ends up anywhere it could be read back from.
+ *
+ * Both ciphers need to map each byte pair to a single char, so modes can't be transcoded.
+ */
+pub fn transcode(
+    from: &dyn DynCipher,
+    to: &dyn DynCipher,
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+) -> Result<()> {
+    let from = pair_cipher(from)?;
+    let to = pair_cipher(to)?;
+    let mut pending = [0; 2];
+    let mut result = transcode_pairs(from, to, reader, writer, &mut pending);
+    zeroize(&mut pending);
+    if result.is_ok() {
+        result = writer.flush().map_err(Error::from);
+    }
+    result
+}
+
+fn pair_cipher(cipher: &dyn DynCipher) -> Result<&dyn PairCipher> {
+    cipher.as_pair_cipher().ok_or_else(|| {
+        Error::msg(format!(
+            "{} doesn't encrypt each byte pair to a single char, so it can't be transcoded",
+            cipher.info().name
+        ))
+    })
+}
+
+// The bytes of a pair decrypted from one cipher don't always line up with a pair of the
+// other, as a pair can be a single byte at the end of a message, so they're queued up in
+// pending first.
+fn transcode_pairs(
+    from: &dyn PairCipher,
+    to: &dyn PairCipher,
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    pending: &mut [u8; 2],
+) -> Result<()> {
+    let mut queued = 0;
+    let mut buf = [0; 4];
+    for encrypted in Chars::new(reader) {
+        let (b0, b1) = from.decrypt_char(encrypted?)?;
+        for byte in [Some(b0), b1].into_iter().flatten() {
+            pending[queued] = byte;
+            queued += 1;
+            if queued == 2 {
+                let encrypted = to.encrypt_pair((pending[0], Some(pending[1])));
+                writer.write_all(encrypted.encode_utf8(&mut buf).as_bytes())?;
+                queued = 0;
+            }
+        }
+    }
+    if queued == 1 {
+        let encrypted = to.encrypt_pair((pending[0], None));
+        writer.write_all(encrypted.encode_utf8(&mut buf).as_bytes())?;
+    }
+    Ok(())
+}
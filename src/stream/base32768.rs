@@ -0,0 +1,168 @@
+use super::Repertoire;
+use crate::cipherv2::Chars;
+use crate::info::{CipherInfo, Describe, Expansion};
+use anyhow::{Error, Result};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::ops::RangeInclusive;
+
+const BITS: u32 = 15;
+const FINAL_BITS: u32 = 7;
+
+// The published repertoires, in order: 4 runs of 32 characters for the 7 bit final character
+// and 1024 runs of 32 for 15 bits, merged where they touch.
+const OUTPUT: [RangeInclusive<u32>; 54] = [
+    0x0180..=0x019F,
+    0x0240..=0x029F,
+    0x04A0..=0x04BF,
+    0x0500..=0x051F,
+    0x0680..=0x06BF,
+    0x0760..=0x079F,
+    0x07C0..=0x07DF,
+    0x1000..=0x101F,
+    0x10A0..=0x10BF,
+    0x1100..=0x115F,
+    0x1180..=0x119F,
+    0x11E0..=0x123F,
+    0x1260..=0x127F,
+    0x12E0..=0x12FF,
+    0x1320..=0x133F,
+    0x13A0..=0x13DF,
+    0x1420..=0x165F,
+    0x16A0..=0x16DF,
+    0x1780..=0x179F,
+    0x1820..=0x185F,
+    0x18C0..=0x18DF,
+    0x1980..=0x199F,
+    0x19E0..=0x19FF,
+    0x1A20..=0x1A3F,
+    0x1BC0..=0x1BDF,
+    0x1C00..=0x1C1F,
+    0x1D00..=0x1D1F,
+    0x21E0..=0x21FF,
+    0x22C0..=0x22DF,
+    0x2340..=0x23DF,
+    0x2400..=0x241F,
+    0x2500..=0x275F,
+    0x2780..=0x27BF,
+    0x2800..=0x297F,
+    0x29A0..=0x29BF,
+    0x2A20..=0x2A5F,
+    0x2A80..=0x2ABF,
+    0x2AE0..=0x2B5F,
+    0x2C00..=0x2C1F,
+    0x2C80..=0x2CDF,
+    0x2D00..=0x2D1F,
+    0x2D40..=0x2D5F,
+    0x2EA0..=0x2EDF,
+    0x31C0..=0x31DF,
+    0x3400..=0x4D9F,
+    0x4DC0..=0x9FBF,
+    0xA000..=0xA47F,
+    0xA4A0..=0xA4BF,
+    0xA500..=0xA5FF,
+    0xA640..=0xA65F,
+    0xA6A0..=0xA6DF,
+    0xA700..=0xA75F,
+    0xA780..=0xA79F,
+    0xA840..=0xA85F,
+];
+const FINAL: &[RangeInclusive<u32>] = OUTPUT.split_at(2).0;
+const FULL: &[RangeInclusive<u32>] = OUTPUT.split_at(2).1;
+
+/**
+ * Base32768 speaks the base32768 encoding other tools use to fit binary into as few
+ * characters as possible. The bytes are read as a stream of bits, most significant first, and
+ * every 15 of them pick a character from the 15 bit repertoire. Whatever's left at the end is
+ * padded with 1 bits, up to 7 if it fits, for a character from the smaller final repertoire,
+ * or up to 15 if it doesn't.
+ */
+pub struct Base32768;
+
+impl Describe for Base32768 {
+    const INFO: CipherInfo = CipherInfo {
+        name: "base32768",
+        id: 13,
+        description: "the base32768 encoding, 15 bits a character",
+        expansion: Expansion::Variable {
+            min: 16.0 / 15.0,
+            max: 1.6,
+        },
+        output_ranges: &OUTPUT,
+        binary_safe: true,
+    };
+}
+
+impl Base32768 {
+    /// The character for the lowest `bits` bits of `group`, which has to be 15 or 7 bits.
+    pub fn encrypt_group(&self, group: u32, bits: u32) -> char {
+        match bits {
+            BITS => Repertoire(FULL).char_at(group),
+            FINAL_BITS => Repertoire(FINAL).char_at(group),
+            _ => panic!("base32768 has no {} bit characters", bits),
+        }
+    }
+
+    /// The group of bits a character stands for, and how many there are.
+    pub fn decrypt_char(&self, encrypted: char) -> Result<(u32, u32)> {
+        if let Some(group) = Repertoire(FULL).index_of(encrypted) {
+            return Ok((group, BITS));
+        }
+        if let Some(group) = Repertoire(FINAL).index_of(encrypted) {
+            return Ok((group, FINAL_BITS));
+        }
+        Err(Error::msg(format!(
+            "{:?} (U+{:04X}) isn't a base32768 character",
+            encrypted, encrypted as u32
+        )))
+    }
+
+    pub fn encrypt<R: Read, W: Write>(&self, reader: R, writer: W) -> Result<()> {
+        let mut writer = BufWriter::new(writer);
+        let mut buf = [0; 3];
+        let (mut acc, mut len) = (0u32, 0);
+        for byte in BufReader::new(reader).bytes() {
+            acc = acc << 8 | byte? as u32;
+            len += 8;
+            if len >= BITS {
+                len -= BITS;
+                let encrypted = self.encrypt_group(acc >> len, BITS).encode_utf8(&mut buf);
+                writer.write_all(encrypted.as_bytes())?;
+                acc &= (1 << len) - 1;
+            }
+        }
+        if len > 0 {
+            let bits = if len <= FINAL_BITS { FINAL_BITS } else { BITS };
+            let padding = bits - len;
+            let group = acc << padding | ((1 << padding) - 1);
+            let encrypted = self.encrypt_group(group, bits).encode_utf8(&mut buf);
+            writer.write_all(encrypted.as_bytes())?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn decrypt<R: Read, W: Write>(&self, reader: R, writer: W) -> Result<()> {
+        let mut writer = BufWriter::new(writer);
+        let (mut acc, mut len) = (0u32, 0);
+        let mut finished = false;
+        for encrypted in Chars::new(BufReader::new(reader)) {
+            if finished {
+                return Err(Error::msg("base32768 carries on after its final character"));
+            }
+            let (group, bits) = self.decrypt_char(encrypted?)?;
+            finished = bits == FINAL_BITS;
+            acc = acc << bits | group;
+            len += bits;
+            while len >= 8 {
+                len -= 8;
+                writer.write_all(&[(acc >> len) as u8])?;
+                acc &= (1 << len) - 1;
+            }
+        }
+        if acc != (1 << len) - 1 {
+            return Err(Error::msg("base32768 padding should be all 1 bits"));
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use super::Repertoire;
+use crate::cipher::BytePair;
+use crate::cipherv2::Chars;
+use crate::info::{CipherInfo, Describe, Expansion};
+use anyhow::{Error, Result};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::ops::RangeInclusive;
+
+// The published repertoires, in order: one block of 256 characters for a lone final byte, and
+// 256 blocks of 256 for pairs, merged where they touch.
+const OUTPUT: [RangeInclusive<u32>; 11] = [
+    0x1500..=0x15FF,
+    0x3400..=0x4CFF,
+    0x4E00..=0x9EFF,
+    0xA100..=0xA3FF,
+    0xA500..=0xA5FF,
+    0x1_0600..=0x1_06FF,
+    0x1_2000..=0x1_22FF,
+    0x1_3000..=0x1_33FF,
+    0x1_4400..=0x1_45FF,
+    0x1_6800..=0x1_69FF,
+    0x2_0000..=0x2_85FF,
+];
+const FINAL: &[RangeInclusive<u32>] = OUTPUT.split_at(1).0;
+const PAIRS: &[RangeInclusive<u32>] = OUTPUT.split_at(1).1;
+
+/**
+ * Base65536 speaks the base65536 encoding other tools use, a character for every pair of
+ * bytes. The second byte of the pair picks one of 256 blocks of 256 characters and the first
+ * byte the character within it, so "hello world" comes out as 驨ꍬ啯𒁷ꍲᕤ. A lone final byte
+ * has a block of its own.
+ */
+pub struct Base65536;
+
+impl Describe for Base65536 {
+    const INFO: CipherInfo = CipherInfo {
+        name: "base65536",
+        id: 14,
+        description: "the base65536 encoding, a character for every byte pair",
+        expansion: Expansion::Variable { min: 1.5, max: 2.0 },
+        output_ranges: &OUTPUT,
+        binary_safe: true,
+    };
+}
+
+impl Base65536 {
+    pub fn encrypt_char_pair(&self, pair: BytePair) -> char {
+        match pair {
+            (c0, Some(c1)) => Repertoire(PAIRS).char_at((c1 as u32) << 8 | c0 as u32),
+            (c0, None) => Repertoire(FINAL).char_at(c0 as u32),
+        }
+    }
+
+    pub fn decrypt_char(&self, encrypted: char) -> Result<BytePair> {
+        if let Some(index) = Repertoire(PAIRS).index_of(encrypted) {
+            return Ok((index as u8, Some((index >> 8) as u8)));
+        }
+        if let Some(index) = Repertoire(FINAL).index_of(encrypted) {
+            return Ok((index as u8, None));
+        }
+        Err(Error::msg(format!(
+            "{:?} (U+{:04X}) isn't a base65536 character",
+            encrypted, encrypted as u32
+        )))
+    }
+
+    pub fn encrypt<R: Read, W: Write>(&self, reader: R, writer: W) -> Result<()> {
+        let mut writer = BufWriter::new(writer);
+        let mut bytes = BufReader::new(reader).bytes();
+        let mut buf = [0; 4];
+        while let Some(c0) = bytes.next() {
+            let pair = (c0?, bytes.next().transpose()?);
+            let encrypted = self.encrypt_char_pair(pair).encode_utf8(&mut buf);
+            writer.write_all(encrypted.as_bytes())?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn decrypt<R: Read, W: Write>(&self, reader: R, writer: W) -> Result<()> {
+        let mut writer = BufWriter::new(writer);
+        let mut finished = false;
+        for encrypted in Chars::new(BufReader::new(reader)) {
+            if finished {
+                return Err(Error::msg("base65536 carries on after its final character"));
+            }
+            match self.decrypt_char(encrypted?)? {
+                (c0, Some(c1)) => writer.write_all(&[c0, c1])?,
+                (c0, None) => {
+                    writer.write_all(&[c0])?;
+                    finished = true;
+                }
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
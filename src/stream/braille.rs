@@ -0,0 +1,60 @@
+use crate::cipherv2::Chars;
+use crate::info::{CipherInfo, Describe, Expansion};
+use anyhow::{Error, Result};
+use std::io::{BufReader, BufWriter, Read, Write};
+
+const BRAILLE: u32 = 0x2800;
+
+/**
+ * Braille writes each byte as one of the 256 Braille patterns, U+2800 + byte. As there's a
+ * pattern for every byte there are no pairs to make, nothing to pad and nothing to flag, and
+ * the dots show up in any terminal.
+ */
+pub struct Braille;
+
+impl Describe for Braille {
+    const INFO: CipherInfo = CipherInfo {
+        name: "braille",
+        id: 11,
+        description: "each byte as a Braille pattern",
+        expansion: Expansion::Fixed(3.0),
+        output_ranges: &[0x2800..=0x28FF],
+        binary_safe: true,
+    };
+}
+
+impl Braille {
+    pub fn encrypt_byte(&self, byte: u8) -> char {
+        // we should always produce valid utf8, if not, that's a bug and we should panic.
+        char::from_u32(BRAILLE + byte as u32).unwrap()
+    }
+
+    pub fn decrypt_char(&self, encrypted: char) -> Result<u8> {
+        u8::try_from((encrypted as u32).wrapping_sub(BRAILLE)).map_err(|_| {
+            Error::msg(format!(
+                "{:?} (U+{:04X}) isn't a Braille pattern",
+                encrypted, encrypted as u32
+            ))
+        })
+    }
+
+    pub fn encrypt<R: Read, W: Write>(&self, reader: R, writer: W) -> Result<()> {
+        let mut writer = BufWriter::new(writer);
+        let mut buf = [0; 3];
+        for byte in BufReader::new(reader).bytes() {
+            let encrypted = self.encrypt_byte(byte?).encode_utf8(&mut buf);
+            writer.write_all(encrypted.as_bytes())?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn decrypt<R: Read, W: Write>(&self, reader: R, writer: W) -> Result<()> {
+        let mut writer = BufWriter::new(writer);
+        for encrypted in Chars::new(BufReader::new(reader)) {
+            writer.write_all(&[self.decrypt_char(encrypted?)?])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,353 @@
+use super::marks::is_mark;
+use super::{Alphabet, Radix, RadixCipher};
+use crate::crypto::sha256;
+use crate::info::{CipherInfo, Expansion};
+use anyhow::{Error, Result};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+/// Custom alphabets all share an ID, the hash in their name tells them apart.
+pub const CUSTOM_ID: u8 = 6;
+pub const CUSTOM_PREFIX: &str = "alphabet";
+const MIN_SIZE: usize = 16;
+// 16 bits a character is already as dense as a byte pair, and every base up to here leaves
+// Radix's blocks small enough to fit in a u128.
+pub(super) const MAX_SIZE: usize = 1 << 16;
+// How much of the alphabet's hash goes in its name, in bytes.
+const HASH_LEN: usize = 8;
+
+// Zero width and other invisible formatting characters. Like combining marks, they'd vanish
+// into the characters around them.
+const INVISIBLE: [RangeInclusive<u32>; 3] = [0x200B..=0x200F, 0x202A..=0x202E, 0x2060..=0x206F];
+
+/**
+ * CustomAlphabet is a cipher built at runtime from characters the user picked, so teams can
+ * have ciphertext that looks the way they like. The message is written out in base
+ * alphabet.len() by a Radix, one character per digit, in the order the alphabet lists them.
+ *
+ * The cipher's name includes a hash of the alphabet, alphabet:1a2b3c4d5e6f7a8b, so a message
+ * sealed in an envelope records exactly which alphabet it needs. Building one leaks its
+ * CipherInfo, as a cipher's description has to live as long as the program.
+ */
+pub struct CustomAlphabet {
+    chars: Vec<char>,
+    digits: HashMap<char, u32>,
+    radix: Radix,
+    info: &'static CipherInfo,
+}
+
+impl CustomAlphabet {
+    pub fn new(chars: Vec<char>) -> Result<Self> {
+        let mut digits = HashMap::with_capacity(chars.len());
+        for (digit, c) in chars.iter().enumerate() {
+            check_char(*c)?;
+            if digits.insert(*c, digit as u32).is_some() {
+                return Err(Error::msg(format!(
+                    "{:?} (U+{:04X}) is in the alphabet twice",
+                    c, *c as u32
+                )));
+            }
+        }
+        if chars.len() < MIN_SIZE {
+            return Err(Error::msg(format!(
+                "an alphabet needs at least {} characters, this one has {}",
+                MIN_SIZE,
+                chars.len()
+            )));
+        }
+        if chars.len() > MAX_SIZE {
+            return Err(Error::msg(format!(
+                "an alphabet can have at most {} characters, this one has {}",
+                MAX_SIZE,
+                chars.len()
+            )));
+        }
+        let radix = Radix::new(chars.len() as u32);
+        let info = Box::leak(Box::new(describe(&chars, &radix)));
+        Ok(CustomAlphabet {
+            chars,
+            digits,
+            radix,
+            info,
+        })
+    }
+
+    /// An alphabet written out as text, every character other than whitespace in order.
+    pub fn from_text(text: &str) -> Result<Self> {
+        CustomAlphabet::new(text.chars().filter(|c| !c.is_whitespace()).collect())
+    }
+
+    /**
+     * An alphabet as a TOML file with two keys: `ranges`, an array of code point ranges such as
+     * "U+3041-U+3096", or lone code points such as "U+1F600", and `chars`, a string of
+     * characters to add after them. Strings can be basic strings, with TOML's escapes, or
+     * 'literal' strings; tables and the other value types aren't used.
+     */
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        let mut chars = Vec::new();
+        for (key, values) in parse_toml(toml)? {
+            match key.as_str() {
+                "ranges" => {
+                    for range in values {
+                        chars.extend(parse_range(&range)?);
+                    }
+                }
+                "chars" => chars.extend(values.iter().flat_map(|value| value.chars())),
+                _ => {
+                    return Err(Error::msg(format!(
+                        "unknown key '{}', expected ranges or chars",
+                        key
+                    )))
+                }
+            }
+        }
+        CustomAlphabet::new(chars)
+    }
+
+    pub fn info(&self) -> &'static CipherInfo {
+        self.info
+    }
+
+    /// The start of a sha256 of the alphabet, in hex.
+    pub fn hash(&self) -> &str {
+        &self.info.name[CUSTOM_PREFIX.len() + 1..]
+    }
+}
+
+impl Alphabet for CustomAlphabet {
+    fn len(&self) -> u32 {
+        self.chars.len() as u32
+    }
+
+    fn char_at(&self, digit: u32) -> char {
+        self.chars[digit as usize]
+    }
+
+    fn digit_of(&self, c: char) -> Option<u32> {
+        self.digits.get(&c).copied()
+    }
+}
+
+impl RadixCipher for CustomAlphabet {
+    fn alphabet(&self) -> &dyn Alphabet {
+        self
+    }
+
+    fn radix(&self) -> &Radix {
+        &self.radix
+    }
+}
+
+fn check_char(c: char) -> Result<()> {
+    let code_point = c as u32;
+    let problem = if c.is_whitespace() {
+        "whitespace"
+    } else if c.is_control() {
+        "a control character"
+    } else if is_mark(code_point) {
+        "a combining mark"
+    } else if INVISIBLE.iter().any(|range| range.contains(&code_point)) {
+        "an invisible formatting character"
+    } else if (0xFDD0..=0xFDEF).contains(&code_point) || code_point & 0xFFFE == 0xFFFE {
+        // Noncharacters are reserved, the envelope marker among them.
+        "a noncharacter"
+    } else {
+        return Ok(());
+    };
+    Err(Error::msg(format!(
+        "{:?} (U+{:04X}) can't be in an alphabet, it's {}",
+        c, code_point, problem
+    )))
+}
+
+fn describe(chars: &[char], radix: &Radix) -> CipherInfo {
+    let mut encoded = String::with_capacity(chars.len() * 4);
+    encoded.extend(chars);
+    let hash: String = sha256(encoded.as_bytes())[..HASH_LEN]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+
+    let mut code_points: Vec<u32> = chars.iter().map(|c| *c as u32).collect();
+    code_points.sort_unstable();
+    let mut ranges: Vec<RangeInclusive<u32>> = Vec::new();
+    for code_point in code_points {
+        match ranges.last_mut() {
+            Some(range) if *range.end() + 1 == code_point => {
+                *range = *range.start()..=code_point;
+            }
+            _ => ranges.push(code_point..=code_point),
+        }
+    }
+
+    let digits_per_byte = radix.block_digits() as f32 / radix.block_bytes() as f32;
+    let widths = chars.iter().map(|c| c.len_utf8());
+    let (min, max) = (widths.clone().min(), widths.max());
+    let (min, max) = (min.unwrap_or(1) as f32, max.unwrap_or(1) as f32);
+    let expansion = if min == max {
+        Expansion::Fixed(min * digits_per_byte)
+    } else {
+        Expansion::Variable {
+            min: min * digits_per_byte,
+            max: max * digits_per_byte,
+        }
+    };
+
+    CipherInfo {
+        name: Box::leak(format!("{}:{}", CUSTOM_PREFIX, hash).into_boxed_str()),
+        id: CUSTOM_ID,
+        description: "a custom alphabet",
+        expansion,
+        output_ranges: Box::leak(ranges.into_boxed_slice()),
+        binary_safe: true,
+    }
+}
+
+fn parse_range(range: &str) -> Result<impl Iterator<Item = char>> {
+    let invalid = || {
+        Error::msg(format!(
+            "invalid range '{}', expected U+XXXX-U+YYYY or U+XXXX",
+            range
+        ))
+    };
+    let code_point = |code_point: &str| -> Result<u32> {
+        let hex = code_point
+            .trim()
+            .strip_prefix("U+")
+            .or_else(|| code_point.trim().strip_prefix("u+"))
+            .ok_or_else(invalid)?;
+        u32::from_str_radix(hex, 16).map_err(|_| invalid())
+    };
+    let (start, end) = match range.split_once("..").or_else(|| range.split_once('-')) {
+        Some((start, end)) => (code_point(start)?, code_point(end)?),
+        None => (code_point(range)?, code_point(range)?),
+    };
+    if start > end || end > char::MAX as u32 {
+        return Err(invalid());
+    }
+    if let Some(surrogate) = (start..=end).find(|c| (0xD800..=0xDFFF).contains(c)) {
+        return Err(Error::msg(format!(
+            "range '{}' includes U+{:04X}, a surrogate",
+            range, surrogate
+        )));
+    }
+    // Surrogates are ruled out above, so every code point is a char.
+    Ok((start..=end).filter_map(char::from_u32))
+}
+
+// Parses just enough TOML for an alphabet: keys set to a string, or an array of strings, where
+// strings are basic strings with TOML's escapes or 'literal' strings.
+fn parse_toml(toml: &str) -> Result<Vec<(String, Vec<String>)>> {
+    let mut entries = Vec::new();
+    let mut chars = toml.chars().peekable();
+    loop {
+        skip_blank(&mut chars);
+        if chars.peek().is_none() {
+            return Ok(entries);
+        }
+        let key: String = std::iter::from_fn(|| {
+            chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+        })
+        .collect();
+        skip_blank(&mut chars);
+        if key.is_empty() || chars.next() != Some('=') {
+            return Err(Error::msg("expected a key = value line"));
+        }
+        skip_blank(&mut chars);
+        let values = match chars.peek() {
+            Some('[') => {
+                chars.next();
+                let mut values = Vec::new();
+                loop {
+                    skip_blank(&mut chars);
+                    match chars.peek() {
+                        Some(']') => {
+                            chars.next();
+                            break;
+                        }
+                        Some('"' | '\'') => values.push(parse_string(&mut chars)?),
+                        _ => return Err(Error::msg(format!("'{}' isn't a list of strings", key))),
+                    }
+                    skip_blank(&mut chars);
+                    match chars.next() {
+                        Some(',') => continue,
+                        Some(']') => break,
+                        _ => return Err(Error::msg(format!("'{}' isn't a list of strings", key))),
+                    }
+                }
+                values
+            }
+            Some('"' | '\'') => vec![parse_string(&mut chars)?],
+            _ => {
+                return Err(Error::msg(format!(
+                    "'{}' should be a string or a list",
+                    key
+                )))
+            }
+        };
+        entries.push((key, values));
+    }
+}
+
+fn skip_blank(chars: &mut std::iter::Peekable<std::str::Chars>) {
+    while let Some(c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if *c == '#' {
+            while chars.next_if(|c| *c != '\n').is_some() {}
+        } else {
+            return;
+        }
+    }
+}
+
+fn parse_string(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<String> {
+    let unterminated = || Error::msg("unterminated string");
+    // A literal string, in single quotes, takes every character as it stands.
+    if chars.next() == Some('\'') {
+        let mut string = String::new();
+        loop {
+            match chars.next().ok_or_else(unterminated)? {
+                '\'' => return Ok(string),
+                '\n' => return Err(unterminated()),
+                c => string.push(c),
+            }
+        }
+    }
+    let mut string = String::new();
+    loop {
+        match chars.next().ok_or_else(unterminated)? {
+            '"' => return Ok(string),
+            '\\' => string.push(match chars.next().ok_or_else(unterminated)? {
+                'b' => '\u{8}',
+                't' => '\t',
+                'n' => '\n',
+                'f' => '\u{c}',
+                'r' => '\r',
+                '"' => '"',
+                '\\' => '\\',
+                'u' => parse_unicode_escape(chars, 4)?,
+                'U' => parse_unicode_escape(chars, 8)?,
+                escape => {
+                    return Err(Error::msg(format!(
+                        "unsupported escape '\\{}' in a string",
+                        escape
+                    )))
+                }
+            }),
+            '\n' => return Err(unterminated()),
+            c => string.push(c),
+        }
+    }
+}
+
+fn parse_unicode_escape(
+    chars: &mut std::iter::Peekable<std::str::Chars>,
+    digits: usize,
+) -> Result<char> {
+    let hex: String = chars.take(digits).collect();
+    Some(&hex)
+        .filter(|hex| hex.len() == digits && hex.chars().all(|c| c.is_ascii_hexdigit()))
+        .and_then(|hex| char::from_u32(u32::from_str_radix(hex, 16).ok()?))
+        .ok_or_else(|| Error::msg(format!("'{}' isn't a unicode escape", hex)))
+}
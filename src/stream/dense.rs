@@ -0,0 +1,108 @@
+use super::{Packer, Unpacker};
+use crate::cipherv2::Chars;
+use crate::info::{CipherInfo, Describe, Expansion};
+use anyhow::{Error, Result};
+use std::io::{BufReader, BufWriter, Read, Write};
+
+const BITS: u32 = 20;
+const SUPPLEMENTARY: u32 = 0x1_0000;
+// The 32 supplementary noncharacters, U+1FFFE, U+1FFFF, U+2FFFE and so on, are swapped for
+// the first 32 code points of the private use area.
+const NONCHARACTERS: u32 = 0xE000;
+
+/**
+ * Dense packs 20 bits into every character, the most a supplementary plane code point can
+ * hold, rather than the 16 of a byte pair. Group v becomes U+10000 + v, unless that's a
+ * noncharacter, in which case it moves down to the private use area.
+ */
+pub struct Dense;
+
+impl Describe for Dense {
+    const INFO: CipherInfo = CipherInfo {
+        name: "dense",
+        id: 5,
+        description: "20 bits packed into each supplementary plane code point",
+        expansion: Expansion::Variable { min: 1.2, max: 1.6 },
+        output_ranges: &[
+            0xE000..=0xE01F,
+            0x1_0000..=0x1_FFFD,
+            0x2_0000..=0x2_FFFD,
+            0x3_0000..=0x3_FFFD,
+            0x4_0000..=0x4_FFFD,
+            0x5_0000..=0x5_FFFD,
+            0x6_0000..=0x6_FFFD,
+            0x7_0000..=0x7_FFFD,
+            0x8_0000..=0x8_FFFD,
+            0x9_0000..=0x9_FFFD,
+            0xA_0000..=0xA_FFFD,
+            0xB_0000..=0xB_FFFD,
+            0xC_0000..=0xC_FFFD,
+            0xD_0000..=0xD_FFFD,
+            0xE_0000..=0xE_FFFD,
+            0xF_0000..=0xF_FFFD,
+            0x10_0000..=0x10_FFFD,
+        ],
+        binary_safe: true,
+    };
+}
+
+impl Dense {
+    pub fn encrypt_group(&self, group: u32) -> char {
+        let code_point = SUPPLEMENTARY + group;
+        let code_point = if code_point & 0xFFFE == 0xFFFE {
+            NONCHARACTERS + ((code_point >> 16) - 1) * 2 + (code_point & 1)
+        } else {
+            code_point
+        };
+        // we should always produce valid utf8, if not, that's a bug and we should panic.
+        char::from_u32(code_point).unwrap()
+    }
+
+    pub fn decrypt_char(&self, encrypted: char) -> Result<u32> {
+        let code_point = encrypted as u32;
+        match code_point {
+            NONCHARACTERS..=0xE01F => {
+                let index = code_point - NONCHARACTERS;
+                Ok(((index / 2) << 16) + 0xFFFE + (index & 1))
+            }
+            _ if code_point >= SUPPLEMENTARY && code_point & 0xFFFE != 0xFFFE => {
+                Ok(code_point - SUPPLEMENTARY)
+            }
+            _ => Err(Error::msg(format!(
+                "{:?} (U+{:04X}) can't have come from the dense cipher",
+                encrypted, code_point
+            ))),
+        }
+    }
+
+    pub fn encrypt<R: Read, W: Write>(&self, reader: R, writer: W) -> Result<()> {
+        let mut writer = BufWriter::new(writer);
+        let mut packer = Packer::new(BITS);
+        let mut buf = [0; 4];
+        for byte in BufReader::new(reader).bytes() {
+            packer.push(byte?);
+            while let Some(group) = packer.next_group() {
+                let encrypted = self.encrypt_group(group).encode_utf8(&mut buf);
+                writer.write_all(encrypted.as_bytes())?;
+            }
+        }
+        let encrypted = self.encrypt_group(packer.finish()).encode_utf8(&mut buf);
+        writer.write_all(encrypted.as_bytes())?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn decrypt<R: Read, W: Write>(&self, reader: R, writer: W) -> Result<()> {
+        let mut writer = BufWriter::new(writer);
+        let mut unpacker = Unpacker::new(BITS);
+        for encrypted in Chars::new(BufReader::new(reader)) {
+            unpacker.push(self.decrypt_char(encrypted?)?);
+            while let Some(byte) = unpacker.next_byte() {
+                writer.write_all(&[byte])?;
+            }
+        }
+        writer.write_all(&unpacker.finish()?)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
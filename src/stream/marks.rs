@@ -0,0 +1,321 @@
+use std::ops::RangeInclusive;
+
+// Every code point in general categories Mn, Mc and Me, the nonspacing, spacing and enclosing
+// marks, from version 14.0.0 of the Unicode Character Database. Regenerate it by walking the
+// code points of a newer database and merging the runs of marks.
+const MARKS: [RangeInclusive<u32>; 299] = [
+    0x0300..=0x036F,
+    0x0483..=0x0489,
+    0x0591..=0x05BD,
+    0x05BF..=0x05BF,
+    0x05C1..=0x05C2,
+    0x05C4..=0x05C5,
+    0x05C7..=0x05C7,
+    0x0610..=0x061A,
+    0x064B..=0x065F,
+    0x0670..=0x0670,
+    0x06D6..=0x06DC,
+    0x06DF..=0x06E4,
+    0x06E7..=0x06E8,
+    0x06EA..=0x06ED,
+    0x0711..=0x0711,
+    0x0730..=0x074A,
+    0x07A6..=0x07B0,
+    0x07EB..=0x07F3,
+    0x07FD..=0x07FD,
+    0x0816..=0x0819,
+    0x081B..=0x0823,
+    0x0825..=0x0827,
+    0x0829..=0x082D,
+    0x0859..=0x085B,
+    0x0898..=0x089F,
+    0x08CA..=0x08E1,
+    0x08E3..=0x0903,
+    0x093A..=0x093C,
+    0x093E..=0x094F,
+    0x0951..=0x0957,
+    0x0962..=0x0963,
+    0x0981..=0x0983,
+    0x09BC..=0x09BC,
+    0x09BE..=0x09C4,
+    0x09C7..=0x09C8,
+    0x09CB..=0x09CD,
+    0x09D7..=0x09D7,
+    0x09E2..=0x09E3,
+    0x09FE..=0x09FE,
+    0x0A01..=0x0A03,
+    0x0A3C..=0x0A3C,
+    0x0A3E..=0x0A42,
+    0x0A47..=0x0A48,
+    0x0A4B..=0x0A4D,
+    0x0A51..=0x0A51,
+    0x0A70..=0x0A71,
+    0x0A75..=0x0A75,
+    0x0A81..=0x0A83,
+    0x0ABC..=0x0ABC,
+    0x0ABE..=0x0AC5,
+    0x0AC7..=0x0AC9,
+    0x0ACB..=0x0ACD,
+    0x0AE2..=0x0AE3,
+    0x0AFA..=0x0AFF,
+    0x0B01..=0x0B03,
+    0x0B3C..=0x0B3C,
+    0x0B3E..=0x0B44,
+    0x0B47..=0x0B48,
+    0x0B4B..=0x0B4D,
+    0x0B55..=0x0B57,
+    0x0B62..=0x0B63,
+    0x0B82..=0x0B82,
+    0x0BBE..=0x0BC2,
+    0x0BC6..=0x0BC8,
+    0x0BCA..=0x0BCD,
+    0x0BD7..=0x0BD7,
+    0x0C00..=0x0C04,
+    0x0C3C..=0x0C3C,
+    0x0C3E..=0x0C44,
+    0x0C46..=0x0C48,
+    0x0C4A..=0x0C4D,
+    0x0C55..=0x0C56,
+    0x0C62..=0x0C63,
+    0x0C81..=0x0C83,
+    0x0CBC..=0x0CBC,
+    0x0CBE..=0x0CC4,
+    0x0CC6..=0x0CC8,
+    0x0CCA..=0x0CCD,
+    0x0CD5..=0x0CD6,
+    0x0CE2..=0x0CE3,
+    0x0D00..=0x0D03,
+    0x0D3B..=0x0D3C,
+    0x0D3E..=0x0D44,
+    0x0D46..=0x0D48,
+    0x0D4A..=0x0D4D,
+    0x0D57..=0x0D57,
+    0x0D62..=0x0D63,
+    0x0D81..=0x0D83,
+    0x0DCA..=0x0DCA,
+    0x0DCF..=0x0DD4,
+    0x0DD6..=0x0DD6,
+    0x0DD8..=0x0DDF,
+    0x0DF2..=0x0DF3,
+    0x0E31..=0x0E31,
+    0x0E34..=0x0E3A,
+    0x0E47..=0x0E4E,
+    0x0EB1..=0x0EB1,
+    0x0EB4..=0x0EBC,
+    0x0EC8..=0x0ECD,
+    0x0F18..=0x0F19,
+    0x0F35..=0x0F35,
+    0x0F37..=0x0F37,
+    0x0F39..=0x0F39,
+    0x0F3E..=0x0F3F,
+    0x0F71..=0x0F84,
+    0x0F86..=0x0F87,
+    0x0F8D..=0x0F97,
+    0x0F99..=0x0FBC,
+    0x0FC6..=0x0FC6,
+    0x102B..=0x103E,
+    0x1056..=0x1059,
+    0x105E..=0x1060,
+    0x1062..=0x1064,
+    0x1067..=0x106D,
+    0x1071..=0x1074,
+    0x1082..=0x108D,
+    0x108F..=0x108F,
+    0x109A..=0x109D,
+    0x135D..=0x135F,
+    0x1712..=0x1715,
+    0x1732..=0x1734,
+    0x1752..=0x1753,
+    0x1772..=0x1773,
+    0x17B4..=0x17D3,
+    0x17DD..=0x17DD,
+    0x180B..=0x180D,
+    0x180F..=0x180F,
+    0x1885..=0x1886,
+    0x18A9..=0x18A9,
+    0x1920..=0x192B,
+    0x1930..=0x193B,
+    0x1A17..=0x1A1B,
+    0x1A55..=0x1A5E,
+    0x1A60..=0x1A7C,
+    0x1A7F..=0x1A7F,
+    0x1AB0..=0x1ACE,
+    0x1B00..=0x1B04,
+    0x1B34..=0x1B44,
+    0x1B6B..=0x1B73,
+    0x1B80..=0x1B82,
+    0x1BA1..=0x1BAD,
+    0x1BE6..=0x1BF3,
+    0x1C24..=0x1C37,
+    0x1CD0..=0x1CD2,
+    0x1CD4..=0x1CE8,
+    0x1CED..=0x1CED,
+    0x1CF4..=0x1CF4,
+    0x1CF7..=0x1CF9,
+    0x1DC0..=0x1DFF,
+    0x20D0..=0x20F0,
+    0x2CEF..=0x2CF1,
+    0x2D7F..=0x2D7F,
+    0x2DE0..=0x2DFF,
+    0x302A..=0x302F,
+    0x3099..=0x309A,
+    0xA66F..=0xA672,
+    0xA674..=0xA67D,
+    0xA69E..=0xA69F,
+    0xA6F0..=0xA6F1,
+    0xA802..=0xA802,
+    0xA806..=0xA806,
+    0xA80B..=0xA80B,
+    0xA823..=0xA827,
+    0xA82C..=0xA82C,
+    0xA880..=0xA881,
+    0xA8B4..=0xA8C5,
+    0xA8E0..=0xA8F1,
+    0xA8FF..=0xA8FF,
+    0xA926..=0xA92D,
+    0xA947..=0xA953,
+    0xA980..=0xA983,
+    0xA9B3..=0xA9C0,
+    0xA9E5..=0xA9E5,
+    0xAA29..=0xAA36,
+    0xAA43..=0xAA43,
+    0xAA4C..=0xAA4D,
+    0xAA7B..=0xAA7D,
+    0xAAB0..=0xAAB0,
+    0xAAB2..=0xAAB4,
+    0xAAB7..=0xAAB8,
+    0xAABE..=0xAABF,
+    0xAAC1..=0xAAC1,
+    0xAAEB..=0xAAEF,
+    0xAAF5..=0xAAF6,
+    0xABE3..=0xABEA,
+    0xABEC..=0xABED,
+    0xFB1E..=0xFB1E,
+    0xFE00..=0xFE0F,
+    0xFE20..=0xFE2F,
+    0x1_01FD..=0x1_01FD,
+    0x1_02E0..=0x1_02E0,
+    0x1_0376..=0x1_037A,
+    0x1_0A01..=0x1_0A03,
+    0x1_0A05..=0x1_0A06,
+    0x1_0A0C..=0x1_0A0F,
+    0x1_0A38..=0x1_0A3A,
+    0x1_0A3F..=0x1_0A3F,
+    0x1_0AE5..=0x1_0AE6,
+    0x1_0D24..=0x1_0D27,
+    0x1_0EAB..=0x1_0EAC,
+    0x1_0F46..=0x1_0F50,
+    0x1_0F82..=0x1_0F85,
+    0x1_1000..=0x1_1002,
+    0x1_1038..=0x1_1046,
+    0x1_1070..=0x1_1070,
+    0x1_1073..=0x1_1074,
+    0x1_107F..=0x1_1082,
+    0x1_10B0..=0x1_10BA,
+    0x1_10C2..=0x1_10C2,
+    0x1_1100..=0x1_1102,
+    0x1_1127..=0x1_1134,
+    0x1_1145..=0x1_1146,
+    0x1_1173..=0x1_1173,
+    0x1_1180..=0x1_1182,
+    0x1_11B3..=0x1_11C0,
+    0x1_11C9..=0x1_11CC,
+    0x1_11CE..=0x1_11CF,
+    0x1_122C..=0x1_1237,
+    0x1_123E..=0x1_123E,
+    0x1_12DF..=0x1_12EA,
+    0x1_1300..=0x1_1303,
+    0x1_133B..=0x1_133C,
+    0x1_133E..=0x1_1344,
+    0x1_1347..=0x1_1348,
+    0x1_134B..=0x1_134D,
+    0x1_1357..=0x1_1357,
+    0x1_1362..=0x1_1363,
+    0x1_1366..=0x1_136C,
+    0x1_1370..=0x1_1374,
+    0x1_1435..=0x1_1446,
+    0x1_145E..=0x1_145E,
+    0x1_14B0..=0x1_14C3,
+    0x1_15AF..=0x1_15B5,
+    0x1_15B8..=0x1_15C0,
+    0x1_15DC..=0x1_15DD,
+    0x1_1630..=0x1_1640,
+    0x1_16AB..=0x1_16B7,
+    0x1_171D..=0x1_172B,
+    0x1_182C..=0x1_183A,
+    0x1_1930..=0x1_1935,
+    0x1_1937..=0x1_1938,
+    0x1_193B..=0x1_193E,
+    0x1_1940..=0x1_1940,
+    0x1_1942..=0x1_1943,
+    0x1_19D1..=0x1_19D7,
+    0x1_19DA..=0x1_19E0,
+    0x1_19E4..=0x1_19E4,
+    0x1_1A01..=0x1_1A0A,
+    0x1_1A33..=0x1_1A39,
+    0x1_1A3B..=0x1_1A3E,
+    0x1_1A47..=0x1_1A47,
+    0x1_1A51..=0x1_1A5B,
+    0x1_1A8A..=0x1_1A99,
+    0x1_1C2F..=0x1_1C36,
+    0x1_1C38..=0x1_1C3F,
+    0x1_1C92..=0x1_1CA7,
+    0x1_1CA9..=0x1_1CB6,
+    0x1_1D31..=0x1_1D36,
+    0x1_1D3A..=0x1_1D3A,
+    0x1_1D3C..=0x1_1D3D,
+    0x1_1D3F..=0x1_1D45,
+    0x1_1D47..=0x1_1D47,
+    0x1_1D8A..=0x1_1D8E,
+    0x1_1D90..=0x1_1D91,
+    0x1_1D93..=0x1_1D97,
+    0x1_1EF3..=0x1_1EF6,
+    0x1_6AF0..=0x1_6AF4,
+    0x1_6B30..=0x1_6B36,
+    0x1_6F4F..=0x1_6F4F,
+    0x1_6F51..=0x1_6F87,
+    0x1_6F8F..=0x1_6F92,
+    0x1_6FE4..=0x1_6FE4,
+    0x1_6FF0..=0x1_6FF1,
+    0x1_BC9D..=0x1_BC9E,
+    0x1_CF00..=0x1_CF2D,
+    0x1_CF30..=0x1_CF46,
+    0x1_D165..=0x1_D169,
+    0x1_D16D..=0x1_D172,
+    0x1_D17B..=0x1_D182,
+    0x1_D185..=0x1_D18B,
+    0x1_D1AA..=0x1_D1AD,
+    0x1_D242..=0x1_D244,
+    0x1_DA00..=0x1_DA36,
+    0x1_DA3B..=0x1_DA6C,
+    0x1_DA75..=0x1_DA75,
+    0x1_DA84..=0x1_DA84,
+    0x1_DA9B..=0x1_DA9F,
+    0x1_DAA1..=0x1_DAAF,
+    0x1_E000..=0x1_E006,
+    0x1_E008..=0x1_E018,
+    0x1_E01B..=0x1_E021,
+    0x1_E023..=0x1_E024,
+    0x1_E026..=0x1_E02A,
+    0x1_E130..=0x1_E136,
+    0x1_E2AE..=0x1_E2AE,
+    0x1_E2EC..=0x1_E2EF,
+    0x1_E8D0..=0x1_E8D6,
+    0x1_E944..=0x1_E94A,
+    0xE_0100..=0xE_01EF,
+];
+
+/// Whether the code point is a combining mark, which would join onto the char before it.
+pub(super) fn is_mark(code_point: u32) -> bool {
+    MARKS
+        .binary_search_by(|range| {
+            if *range.end() < code_point {
+                std::cmp::Ordering::Less
+            } else if *range.start() > code_point {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .is_ok()
+}
@@ -0,0 +1,129 @@
+mod base32768;
+mod base65536;
+mod braille;
+mod custom;
+mod dense;
+mod marks;
+mod radix;
+mod repertoire;
+mod script;
+#[cfg(test)]
+mod tests;
+mod words;
+
+use anyhow::{Error, Result};
+
+pub use base32768::Base32768;
+pub use base65536::Base65536;
+pub use braille::Braille;
+pub use custom::{CustomAlphabet, CUSTOM_ID, CUSTOM_PREFIX};
+pub use dense::Dense;
+pub use radix::{Alphabet, Radix, RadixCipher};
+pub(crate) use repertoire::Repertoire;
+pub use script::{Script, SCRIPTS};
+pub use words::Words;
+
+/**
+ * Packer splits a stream of bytes into groups of a fixed number of bits, for ciphers that
+ * aren't tied to byte pairs. The last group is finished off with a 1 bit and then zeros, so
+ * the Unpacker can tell where the message really ended.
+ */
+pub(crate) struct Packer {
+    bits: u32,
+    acc: u64,
+    len: u32,
+}
+
+impl Packer {
+    pub fn new(bits: u32) -> Self {
+        assert!((8..=32).contains(&bits), "groups of {} bits", bits);
+        Packer {
+            bits,
+            acc: 0,
+            len: 0,
+        }
+    }
+
+    pub fn push(&mut self, byte: u8) {
+        self.acc = self.acc << 8 | byte as u64;
+        self.len += 8;
+    }
+
+    /// The next full group, if there is one.
+    pub fn next_group(&mut self) -> Option<u32> {
+        if self.len < self.bits {
+            return None;
+        }
+        self.len -= self.bits;
+        let group = (self.acc >> self.len) as u32 & mask(self.bits);
+        self.acc &= (1 << self.len) - 1;
+        Some(group)
+    }
+
+    /// The final group, once every full group has been taken.
+    pub fn finish(self) -> u32 {
+        debug_assert!(self.len < self.bits);
+        let terminated = self.acc << 1 | 1;
+        (terminated << (self.bits - self.len - 1)) as u32
+    }
+}
+
+/// Unpacker reverses a Packer, turning groups of bits back into bytes.
+pub(crate) struct Unpacker {
+    bits: u32,
+    acc: u64,
+    len: u32,
+    // The last group is held back, as it's the one with the end marker in it.
+    pending: Option<u32>,
+}
+
+impl Unpacker {
+    pub fn new(bits: u32) -> Self {
+        Unpacker {
+            bits,
+            acc: 0,
+            len: 0,
+            pending: None,
+        }
+    }
+
+    pub fn push(&mut self, group: u32) {
+        if let Some(pending) = self.pending.replace(group) {
+            self.acc = self.acc << self.bits | pending as u64;
+            self.len += self.bits;
+        }
+    }
+
+    /// The next whole byte, if there is one.
+    pub fn next_byte(&mut self) -> Option<u8> {
+        if self.len < 8 {
+            return None;
+        }
+        self.len -= 8;
+        let byte = (self.acc >> self.len) as u8;
+        self.acc &= (1 << self.len) - 1;
+        Some(byte)
+    }
+
+    /// The bytes left in the last group, once every other byte has been taken.
+    pub fn finish(self) -> Result<Vec<u8>> {
+        debug_assert!(self.len < 8);
+        let Some(last) = self.pending else {
+            return Ok(Vec::new());
+        };
+        if last == 0 {
+            return Err(Error::msg("message is missing its end marker"));
+        }
+        let end = last.trailing_zeros() + 1;
+        let len = self.len + self.bits - end;
+        if !len.is_multiple_of(8) {
+            return Err(Error::msg("message ends part way through a byte"));
+        }
+        let acc = (self.acc << self.bits | last as u64) >> end;
+        Ok((0..len / 8).rev().map(|i| (acc >> (8 * i)) as u8).collect())
+    }
+}
+
+fn mask(bits: u32) -> u32 {
+    ((1u64 << bits) - 1) as u32
+}
@@ -0,0 +1,259 @@
+use crate::cipherv2::Chars;
+use anyhow::{Error, Result};
+use std::io::{BufReader, BufWriter, Read, Write};
+
+// Blocks are converted in a u128, so they can't be any longer than this.
+const MAX_BLOCK: usize = 15;
+
+/**
+ * Radix converts bytes to digits of any base and back again, so that a cipher can use an
+ * alphabet of whatever size suits it, rather than one with exactly 2^16 characters. Bytes
+ * are taken a block at a time, and each block becomes a fixed number of digits. The block
+ * size is whichever wastes the least of the alphabet.
+ *
+ * The final block is usually short. It's written with as few digits as it takes, but then
+ * a 1 byte block and a 2 byte block can end up the same number of digits long, so each
+ * length gets its own range of values: a short block is offset by the values of every
+ * shorter block that's the same number of digits long.
+ */
+#[derive(Clone, Debug)]
+pub struct Radix {
+    base: u32,
+    block_bytes: usize,
+    block_digits: usize,
+    // The digits and offset of a final block, by its length less one.
+    finals: Vec<(usize, u128)>,
+}
+
+impl Radix {
+    pub fn new(base: u32) -> Self {
+        assert!(base >= 2, "can't write anything in base {}", base);
+        (1..=MAX_BLOCK)
+            .filter_map(|block_bytes| Radix::with_block(base, block_bytes))
+            // Fewest digits per byte, then the shortest block.
+            .min_by(|a, b| {
+                (a.block_digits * b.block_bytes)
+                    .cmp(&(b.block_digits * a.block_bytes))
+                    .then(a.block_bytes.cmp(&b.block_bytes))
+            })
+            // A single byte always fits, with enough digits.
+            .unwrap()
+    }
+
+    fn with_block(base: u32, block_bytes: usize) -> Option<Self> {
+        let capacity = |digits: usize| (base as u128).saturating_pow(digits as u32);
+        let block_digits = (1..).find(|digits| capacity(*digits) >> (8 * block_bytes) > 0)?;
+        let mut used = vec![0u128; block_digits + 1];
+        let mut finals = Vec::with_capacity(block_bytes);
+        let mut digits = 1;
+        for len in 1..=block_bytes {
+            let values = 1u128 << (8 * len);
+            while used[digits].saturating_add(values) > capacity(digits) {
+                digits += 1;
+                if digits > block_digits {
+                    return None;
+                }
+            }
+            finals.push((digits, used[digits]));
+            used[digits] += values;
+        }
+        Some(Radix {
+            base,
+            block_bytes,
+            block_digits,
+            finals,
+        })
+    }
+
+    pub fn base(&self) -> u32 {
+        self.base
+    }
+
+    /// How many bytes go into each block.
+    pub fn block_bytes(&self) -> usize {
+        self.block_bytes
+    }
+
+    /// How many digits each block comes out as.
+    pub fn block_digits(&self) -> usize {
+        self.block_digits
+    }
+
+    /// Writes a block out as digits, most significant first. Only the last block may be short.
+    pub fn encode_block(&self, block: &[u8], last: bool, digits: &mut Vec<u32>) {
+        debug_assert!(!block.is_empty() && block.len() <= self.block_bytes);
+        let value = block
+            .iter()
+            .fold(0u128, |value, byte| value << 8 | *byte as u128);
+        let (len, value) = if last {
+            let (len, offset) = self.finals[block.len() - 1];
+            (len, value + offset)
+        } else {
+            (self.block_digits, value)
+        };
+        let start = digits.len();
+        digits.resize(start + len, 0);
+        let mut value = value;
+        for digit in digits[start..].iter_mut().rev() {
+            *digit = (value % self.base as u128) as u32;
+            value /= self.base as u128;
+        }
+    }
+
+    /// Reads a block back from its digits. Only the last block may be short.
+    pub fn decode_block(&self, digits: &[u32], last: bool, bytes: &mut Vec<u8>) -> Result<()> {
+        let mut value = 0u128;
+        for digit in digits {
+            if *digit >= self.base {
+                return Err(Error::msg(format!(
+                    "digit {} is too big for base {}",
+                    digit, self.base
+                )));
+            }
+            // Bases just over 2^16 have room for more than a u128 in a block of digits.
+            value = value
+                .checked_mul(self.base as u128)
+                .and_then(|value| value.checked_add(*digit as u128))
+                .ok_or_else(out_of_range)?;
+        }
+        let len = if last {
+            let finals = (1..).zip(&self.finals);
+            let Some((len, (_, offset))) = finals
+                .filter(|(_, (len, _))| *len == digits.len())
+                .take_while(|(_, (_, offset))| *offset <= value)
+                .last()
+            else {
+                return Err(Error::msg("message ends with a block of the wrong length"));
+            };
+            value -= offset;
+            len
+        } else if digits.len() == self.block_digits {
+            self.block_bytes
+        } else {
+            return Err(Error::msg("message has a block of the wrong length"));
+        };
+        if value >> (8 * len) != 0 {
+            return Err(out_of_range());
+        }
+        bytes.extend((0..len).rev().map(|i| (value >> (8 * i)) as u8));
+        Ok(())
+    }
+}
+
+fn out_of_range() -> Error {
+    Error::msg("message has a block that's out of range")
+}
+
+/**
+ * Alphabet is the set of characters a RadixCipher writes its digits with.
+ */
+pub trait Alphabet {
+    fn len(&self) -> u32;
+    fn char_at(&self, digit: u32) -> char;
+    fn digit_of(&self, c: char) -> Option<u32>;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/**
+ * RadixCipher is a cipher defined by nothing more than an Alphabet. The message is written
+ * out in base alphabet.len(), one character per digit. Implementors hold on to a Radix built
+ * for their alphabet, as working out the best block size isn't free.
+ */
+pub trait RadixCipher {
+    fn alphabet(&self) -> &dyn Alphabet;
+    fn radix(&self) -> &Radix;
+
+    fn encrypt<R, W>(&self, reader: R, writer: W) -> Result<()>
+    where
+        Self: Sized,
+        R: Read,
+        W: Write,
+    {
+        let radix = self.radix();
+        let mut writer = BufWriter::new(writer);
+        let mut block = Vec::with_capacity(radix.block_bytes());
+        let mut digits = Vec::with_capacity(radix.block_digits());
+        for byte in BufReader::new(reader).bytes() {
+            // A full block is only written once we know it isn't the last one.
+            if block.len() == radix.block_bytes() {
+                write_block(self, &block, false, &mut digits, &mut writer)?;
+                block.clear();
+            }
+            block.push(byte?);
+        }
+        if !block.is_empty() {
+            write_block(self, &block, true, &mut digits, &mut writer)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn decrypt<R, W>(&self, reader: R, mut writer: W) -> Result<()>
+    where
+        Self: Sized,
+        R: Read,
+        W: Write,
+    {
+        let (radix, alphabet) = (self.radix(), self.alphabet());
+        let mut digits = Vec::with_capacity(radix.block_digits());
+        let mut bytes = Vec::with_capacity(radix.block_bytes());
+        for encrypted in Chars::new(BufReader::new(reader)) {
+            let encrypted = encrypted?;
+            if digits.len() == radix.block_digits() {
+                bytes.clear();
+                radix.decode_block(&digits, false, &mut bytes)?;
+                writer.write_all(&bytes)?;
+                digits.clear();
+            }
+            let digit = alphabet.digit_of(encrypted).ok_or_else(|| {
+                Error::msg(format!("{:?} isn't in the cipher's alphabet", encrypted))
+            })?;
+            digits.push(digit);
+        }
+        if !digits.is_empty() {
+            bytes.clear();
+            radix.decode_block(&digits, true, &mut bytes)?;
+            writer.write_all(&bytes)?;
+        }
+        Ok(())
+    }
+
+    fn encrypt_to_string(&self, bytes: &[u8]) -> String
+    where
+        Self: Sized,
+    {
+        let mut encrypted = Vec::new();
+        // we should always produce valid utf8, if not, that's a bug and we should panic.
+        self.encrypt(bytes, &mut encrypted).unwrap();
+        String::from_utf8(encrypted).unwrap()
+    }
+
+    fn decrypt_to_vec(&self, encrypted: &str) -> Result<Vec<u8>>
+    where
+        Self: Sized,
+    {
+        let mut decrypted = Vec::with_capacity(encrypted.len());
+        self.decrypt(encrypted.as_bytes(), &mut decrypted)?;
+        Ok(decrypted)
+    }
+}
+
+fn write_block<C: RadixCipher>(
+    cipher: &C,
+    block: &[u8],
+    last: bool,
+    digits: &mut Vec<u32>,
+    writer: &mut impl Write,
+) -> Result<()> {
+    let mut buf = [0; 4];
+    digits.clear();
+    cipher.radix().encode_block(block, last, digits);
+    for digit in digits {
+        let encoded = cipher.alphabet().char_at(*digit).encode_utf8(&mut buf);
+        writer.write_all(encoded.as_bytes())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use std::ops::RangeInclusive;
+
+/**
+ * Repertoire lays runs of code points end to end and numbers the characters in order, the
+ * way the published base32768 and base65536 encodings describe the characters they write.
+ */
+pub(crate) struct Repertoire(pub &'static [RangeInclusive<u32>]);
+
+impl Repertoire {
+    pub fn char_at(&self, mut index: u32) -> char {
+        for range in self.0 {
+            let len = range.end() - range.start() + 1;
+            if index < len {
+                // we should always produce valid utf8, if not, that's a bug and we should panic.
+                return char::from_u32(range.start() + index).unwrap();
+            }
+            index -= len;
+        }
+        panic!("{} is past the end of the repertoire", index);
+    }
+
+    pub fn index_of(&self, c: char) -> Option<u32> {
+        let mut index = 0;
+        for range in self.0 {
+            if range.contains(&(c as u32)) {
+                return Some(index + c as u32 - range.start());
+            }
+            index += range.end() - range.start() + 1;
+        }
+        None
+    }
+}
@@ -0,0 +1,111 @@
+use super::{Alphabet, Radix, RadixCipher};
+use crate::info::{CipherInfo, Expansion};
+
+/// Every script we can write in. The output ranges are the alphabet, in order.
+pub const SCRIPTS: [CipherInfo; 4] = [
+    CipherInfo {
+        name: "script:egyptian",
+        id: 7,
+        description: "Egyptian hieroglyphs",
+        expansion: Expansion::Fixed(3.2),
+        // Leaving out the format controls at the end of the block.
+        output_ranges: &[0x13000..=0x1342E],
+        binary_safe: true,
+    },
+    CipherInfo {
+        name: "script:cjk",
+        id: 8,
+        description: "CJK unified ideographs",
+        expansion: Expansion::Fixed(1.71),
+        output_ranges: &[0x4E00..=0x9FFF],
+        binary_safe: true,
+    },
+    CipherInfo {
+        name: "script:hangul",
+        id: 9,
+        description: "precomposed Hangul syllables",
+        expansion: Expansion::Fixed(1.8),
+        output_ranges: &[0xAC00..=0xD7A3],
+        binary_safe: true,
+    },
+    CipherInfo {
+        name: "script:emoji",
+        id: 10,
+        description: "emoji, from the pictographs and emoticons blocks",
+        expansion: Expansion::Fixed(3.33),
+        // Leaving out the skin tone modifiers, which join onto the emoji before them.
+        output_ranges: &[0x1F300..=0x1F3FA, 0x1F400..=0x1F64F],
+        binary_safe: true,
+    },
+];
+
+/**
+ * Script writes its ciphertext in the characters of a single Unicode block, so it looks like
+ * hieroglyphs, or Hangul, rather than noise. None of the blocks have the 65,536 characters a
+ * byte pair needs, so the message is written out in base block size by a Radix, which can
+ * take a few characters to a pair.
+ *
+ * That's why these are RadixCiphers rather than CipherV2s: CipherV2 turns every pair into
+ * exactly one char and back, which a block this size can't do. The price is that they can't
+ * be used where a cipher has to work a pair at a time, such as transcoding.
+ */
+pub struct Script {
+    info: &'static CipherInfo,
+    radix: Radix,
+}
+
+impl Script {
+    pub fn new(info: &'static CipherInfo) -> Self {
+        let radix = Radix::new(info.output_size());
+        Script { info, radix }
+    }
+
+    /// A cipher for each script.
+    pub fn all() -> impl Iterator<Item = Script> {
+        SCRIPTS.iter().map(Script::new)
+    }
+
+    pub fn info(&self) -> &'static CipherInfo {
+        self.info
+    }
+}
+
+impl Alphabet for Script {
+    fn len(&self) -> u32 {
+        self.info.output_size()
+    }
+
+    fn char_at(&self, digit: u32) -> char {
+        let mut digit = digit;
+        for range in self.info.output_ranges {
+            let len = range.end() - range.start() + 1;
+            if digit < len {
+                // we should always produce valid utf8, if not, that's a bug and we should panic.
+                return char::from_u32(range.start() + digit).unwrap();
+            }
+            digit -= len;
+        }
+        panic!("digit {} is outside {}", digit, self.info.name)
+    }
+
+    fn digit_of(&self, c: char) -> Option<u32> {
+        let mut offset = 0;
+        for range in self.info.output_ranges {
+            if range.contains(&(c as u32)) {
+                return Some(offset + c as u32 - range.start());
+            }
+            offset += range.end() - range.start() + 1;
+        }
+        None
+    }
+}
+
+impl RadixCipher for Script {
+    fn alphabet(&self) -> &dyn Alphabet {
+        self
+    }
+
+    fn radix(&self) -> &Radix {
+        &self.radix
+    }
+}
@@ -0,0 +1,567 @@
+use super::custom::MAX_SIZE;
+use super::{
+    Alphabet, Base32768, Base65536, Braille, CustomAlphabet, Dense, Packer, Radix, RadixCipher,
+    Script, Unpacker, Words, SCRIPTS,
+};
+use crate::info::Describe;
+use crate::info::Expansion;
+
+fn pack(bits: u32, bytes: &[u8]) -> Vec<u32> {
+    let mut packer = Packer::new(bits);
+    let mut groups = Vec::new();
+    for byte in bytes {
+        packer.push(*byte);
+        groups.extend(std::iter::from_fn(|| packer.next_group()));
+    }
+    groups.push(packer.finish());
+    groups
+}
+
+fn unpack(bits: u32, groups: &[u32]) -> anyhow::Result<Vec<u8>> {
+    let mut unpacker = Unpacker::new(bits);
+    let mut bytes = Vec::new();
+    for group in groups {
+        unpacker.push(*group);
+        bytes.extend(std::iter::from_fn(|| unpacker.next_byte()));
+    }
+    bytes.extend(unpacker.finish()?);
+    Ok(bytes)
+}
+
+#[test]
+fn packing_round_trips() {
+    let bytes: Vec<u8> = (0..=255).rev().collect();
+    for bits in [8, 11, 15, 16, 20, 32] {
+        for len in 0..40 {
+            let groups = pack(bits, &bytes[..len]);
+            let expected = (8 * len as u32 + 1).div_ceil(bits) as usize;
+            assert_eq!(expected, groups.len(), "{} bytes in {} bits", len, bits);
+            assert!(groups.iter().all(|group| (*group as u64) < 1 << bits));
+            assert_eq!(&bytes[..len], unpack(bits, &groups).unwrap());
+        }
+    }
+}
+
+#[test]
+fn unpacking_checks_the_end_marker() {
+    assert_eq!(Vec::<u8>::new(), unpack(20, &[]).unwrap());
+    assert!(unpack(20, &[0]).is_err());
+    assert!(unpack(20, &[0xABCDE, 0]).is_err());
+    // A marker that leaves half a byte behind it.
+    assert!(unpack(20, &[0b1000_0000_0000_0000_0000 >> 4]).is_err());
+}
+
+#[test]
+fn dense_maps_every_group() {
+    let mut seen = vec![false; Dense::INFO.output_size() as usize];
+    let offset = |c: char| match c as u32 {
+        code_point @ 0xE000..=0xE01F => code_point - 0xE000,
+        code_point => 32 + code_point - 0x1_0000 - (code_point >> 16) * 2 + 2,
+    };
+    for group in 0..1 << 20 {
+        let encrypted = Dense.encrypt_group(group);
+        assert!(Dense::INFO.emits(encrypted), "emitted {:?}", encrypted);
+        assert_eq!(group, Dense.decrypt_char(encrypted).unwrap());
+        let offset = offset(encrypted) as usize;
+        assert!(!seen[offset], "{:?} emitted twice", encrypted);
+        seen[offset] = true;
+    }
+}
+
+#[test]
+fn dense_skips_noncharacters() {
+    assert_eq!('\u{E000}', Dense.encrypt_group(0xFFFE));
+    assert_eq!('\u{E001}', Dense.encrypt_group(0xFFFF));
+    assert_eq!('\u{E01F}', Dense.encrypt_group(0xF_FFFF));
+    assert_eq!('\u{10000}', Dense.encrypt_group(0));
+    assert_eq!('\u{1FFFD}', Dense.encrypt_group(0xFFFD));
+    for c in ['a', '\u{FFFF}', '\u{E020}', '\u{1FFFE}', '\u{10FFFF}'] {
+        assert!(Dense.decrypt_char(c).is_err(), "decrypted {:?}", c);
+    }
+}
+
+#[test]
+fn dense_packs_twenty_bits_a_char() {
+    let mut encrypted = Vec::new();
+    Dense.encrypt(&[0xAB; 100][..], &mut encrypted).unwrap();
+    let encrypted = String::from_utf8(encrypted).unwrap();
+    // 800 bits and the end marker.
+    assert_eq!(41, encrypted.chars().count());
+    let mut decrypted = Vec::new();
+    Dense.decrypt(encrypted.as_bytes(), &mut decrypted).unwrap();
+    assert_eq!(vec![0xAB; 100], decrypted);
+}
+
+struct Contiguous {
+    start: u32,
+    len: u32,
+    radix: Radix,
+}
+
+impl Contiguous {
+    fn new(start: u32, len: u32) -> Self {
+        Contiguous {
+            start,
+            len,
+            radix: Radix::new(len),
+        }
+    }
+}
+
+impl Alphabet for Contiguous {
+    fn len(&self) -> u32 {
+        self.len
+    }
+
+    fn char_at(&self, digit: u32) -> char {
+        char::from_u32(self.start + digit).unwrap()
+    }
+
+    fn digit_of(&self, c: char) -> Option<u32> {
+        (c as u32)
+            .checked_sub(self.start)
+            .filter(|digit| *digit < self.len)
+    }
+}
+
+impl RadixCipher for Contiguous {
+    fn alphabet(&self) -> &dyn Alphabet {
+        self
+    }
+
+    fn radix(&self) -> &Radix {
+        &self.radix
+    }
+}
+
+#[test]
+fn radix_round_trips_any_base() {
+    let bytes: Vec<u8> = (0..=255).rev().chain(0..=255).collect();
+    for base in [2, 3, 10, 255, 256, 257, 1920, 2048, 32768, 65536, 1_000_000] {
+        let cipher = Contiguous::new(0x1_0000, base);
+        for len in (0..70).chain([511, 512]) {
+            let encrypted = cipher.encrypt_to_string(&bytes[..len]);
+            let decrypted = cipher.decrypt_to_vec(&encrypted).unwrap();
+            assert_eq!(&bytes[..len], decrypted, "{} bytes in base {}", len, base);
+        }
+        // Every length of the final block, all zeros and all ones.
+        for len in 0..=cipher.radix().block_bytes() * 2 {
+            for byte in [0x00, 0xFF] {
+                let encrypted = cipher.encrypt_to_string(&vec![byte; len]);
+                assert_eq!(vec![byte; len], cipher.decrypt_to_vec(&encrypted).unwrap());
+            }
+        }
+    }
+}
+
+#[test]
+fn radix_wastes_little_of_the_alphabet() {
+    for base in [1920, 2048, 32768, 65536] {
+        let radix = Radix::new(base);
+        let ideal = 8.0 / (base as f64).log2();
+        let actual = radix.block_digits() as f64 / radix.block_bytes() as f64;
+        assert!(actual < ideal * 1.07, "base {} used {}", base, actual);
+    }
+    // Telling the lengths of the final block apart costs a power of two a little.
+    let radix = Radix::new(65536);
+    assert_eq!((15, 8), (radix.block_bytes(), radix.block_digits()));
+}
+
+#[test]
+fn radix_final_blocks_are_short() {
+    let cipher = Contiguous::new(0x4E00, 2048);
+    let block_bytes = cipher.radix().block_bytes();
+    for len in 1..=block_bytes {
+        let encrypted = cipher.encrypt_to_string(&vec![0xA5; len]);
+        // Never more than one digit over the 11 bits each one holds.
+        let digits = encrypted.chars().count();
+        assert!(
+            digits <= (8 * len).div_ceil(11) + 1,
+            "{} bytes took {}",
+            len,
+            digits
+        );
+    }
+}
+
+#[test]
+fn radix_rejects_malformed_messages() {
+    let cipher = Contiguous::new(0x4E00, 1920);
+    let radix = cipher.radix().clone();
+    assert!(cipher.decrypt_to_vec("abc").is_err());
+    let max = cipher.char_at(1919).to_string();
+    // A full block of the biggest digit is more than its bytes can hold.
+    let overflowing = max.repeat(radix.block_digits() + 1);
+    assert!(cipher.decrypt_to_vec(&overflowing).is_err());
+    let mut bytes = Vec::new();
+    assert!(radix.decode_block(&[1920], true, &mut bytes).is_err());
+    assert!(radix.decode_block(&[0, 0], false, &mut bytes).is_err());
+}
+
+#[test]
+fn radix_blocks_fit_any_custom_alphabet() {
+    for base in 2..=MAX_SIZE as u32 {
+        let radix = Radix::new(base);
+        let capacity = (base as u128).checked_pow(radix.block_digits() as u32);
+        // 2^16 digits fill a u128 exactly, which still leaves room for the biggest value.
+        assert!(capacity.is_some() || base == 1 << 16, "base {}", base);
+    }
+}
+
+#[test]
+fn radix_rejects_blocks_too_big_to_hold() {
+    // Eight digits of base 65537 go past what a u128 can hold.
+    let radix = Radix::new(65537);
+    assert_eq!((15, 8), (radix.block_bytes(), radix.block_digits()));
+    let digits = vec![65536; radix.block_digits()];
+    for last in [false, true] {
+        let err = radix
+            .decode_block(&digits, last, &mut Vec::new())
+            .unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{}", err);
+    }
+}
+
+#[test]
+fn braille_maps_every_byte() {
+    let mut seen = [false; 256];
+    for byte in 0..=u8::MAX {
+        let encrypted = Braille.encrypt_byte(byte);
+        assert_eq!(0x2800 + byte as u32, encrypted as u32);
+        assert!(Braille::INFO.emits(encrypted));
+        assert_eq!(byte, Braille.decrypt_char(encrypted).unwrap());
+        assert!(!seen[byte as usize]);
+        seen[byte as usize] = true;
+
+        let mut encrypted = Vec::new();
+        Braille.encrypt(&[byte][..], &mut encrypted).unwrap();
+        assert_eq!(3, encrypted.len());
+        let mut decrypted = Vec::new();
+        Braille.decrypt(&encrypted[..], &mut decrypted).unwrap();
+        assert_eq!(vec![byte], decrypted);
+    }
+    let bytes: Vec<u8> = (0..=255).collect();
+    let mut encrypted = Vec::new();
+    Braille.encrypt(&bytes[..], &mut encrypted).unwrap();
+    let encrypted = String::from_utf8(encrypted).unwrap();
+    assert_eq!(256, encrypted.chars().count());
+    let mut decrypted = Vec::new();
+    Braille
+        .decrypt(encrypted.as_bytes(), &mut decrypted)
+        .unwrap();
+    assert_eq!(bytes, decrypted);
+}
+
+#[test]
+fn braille_rejects_other_chars() {
+    for c in ['a', '\u{27FF}', '\u{2900}', '\u{12800}'] {
+        assert!(Braille.decrypt_char(c).is_err(), "decrypted {:?}", c);
+    }
+}
+
+#[test]
+fn custom_alphabets_round_trip() {
+    let text = CustomAlphabet::from_text("abcdefgh\nijklmnop\n qrstuvwxyz\n").unwrap();
+    assert_eq!(26, text.len());
+    let toml = CustomAlphabet::from_toml(
+        r#"
+        # Hiragana, a smiley, then a star.
+        ranges = [
+            "U+3041-U+3096",  # the main block
+            "U+1F600",
+        ]
+        chars = "\u2605"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(86 + 1 + 1, toml.len());
+    assert_eq!(Some(87), toml.digit_of('\u{2605}'));
+    let toml = CustomAlphabet::from_toml(
+        "ranges = ['U+3041-U+3096', \"U+1F600\"]\nchars = '\u{2605}\u{2606}'",
+    )
+    .unwrap();
+    assert_eq!(86 + 1 + 2, toml.len());
+    assert_eq!('\u{1F600}', toml.char_at(86));
+    assert_eq!(Some(88), toml.digit_of('\u{2606}'));
+
+    let bytes: Vec<u8> = (0..=255).collect();
+    for alphabet in [text, toml] {
+        for len in [0, 1, 2, 17, 256] {
+            let encrypted = alphabet.encrypt_to_string(&bytes[..len]);
+            assert!(encrypted.chars().all(|c| alphabet.info().emits(c)));
+            assert_eq!(&bytes[..len], alphabet.decrypt_to_vec(&encrypted).unwrap());
+        }
+    }
+}
+
+#[test]
+fn custom_alphabets_are_named_by_their_hash() {
+    let forwards = CustomAlphabet::from_text("abcdefghijklmnop").unwrap();
+    let again = CustomAlphabet::from_text("abcdefgh ijklmnop").unwrap();
+    let backwards = CustomAlphabet::from_text("ponmlkjihgfedcba").unwrap();
+    assert_eq!(forwards.info().name, again.info().name);
+    assert_ne!(forwards.info().name, backwards.info().name);
+    assert!(forwards.info().name.starts_with("alphabet:"));
+    assert_eq!(16, forwards.hash().len());
+    assert_eq!(&[0x61..=0x70], forwards.info().output_ranges);
+}
+
+#[test]
+fn custom_alphabets_are_validated() {
+    let too_big: String = (0x2_0000..=0x2_FFFD)
+        .chain(0x3_0000..=0x3_0002)
+        .filter_map(char::from_u32)
+        .collect();
+    for (alphabet, problem) in [
+        ("abcdefghijklmnopa", "twice"),
+        ("abcdefghijklmno", "at least 16"),
+        (&too_big, "at most 65536"),
+        ("abcdefghijklmnop\u{301}", "combining"),
+        ("abcdefghijklmnop\u{93E}", "combining"),
+        ("abcdefghijklmnop\u{591}", "combining"),
+        ("abcdefghijklmnop\u{64B}", "combining"),
+        ("abcdefghijklmnop\u{E31}", "combining"),
+        ("abcdefghijklmnop\u{20DD}", "combining"),
+        ("abcdefghijklmnop\u{200D}", "invisible"),
+        ("abcdefghijklmnop\u{7}", "control"),
+        ("abcdefghijklmnop\u{FDD0}", "noncharacter"),
+        ("abcdefghijklmnop\u{1FFFF}", "noncharacter"),
+    ] {
+        let err = CustomAlphabet::from_text(alphabet).err().unwrap();
+        assert!(err.to_string().contains(problem), "{}", err);
+    }
+    for (toml, problem) in [
+        (
+            "ranges = [\"U+20000-U+2FFFD\", \"U+30000-U+30002\"]",
+            "at most 65536",
+        ),
+        ("ranges = [\"U+D7F0-U+D80F\"]", "surrogate"),
+        ("ranges = [\"U+0020-U+007E\"]", "whitespace"),
+        ("ranges = [\"U+0041-U+0030\"]", "invalid range"),
+        ("ranges = [\"0041-005A\"]", "invalid range"),
+        ("ranges = [\"U+110000\"]", "invalid range"),
+        ("letters = \"abc\"", "unknown key"),
+        ("ranges = [\"U+0041-U+005A\"", "list of strings"),
+        ("chars = \"abc", "unterminated"),
+        ("chars = 'abc", "unterminated"),
+        ("chars = \"\\q\"", "unsupported escape"),
+        ("chars = \"\\u26\"", "unicode escape"),
+        ("chars = \"\\uD800\"", "unicode escape"),
+        ("chars = \"\\U00110000\"", "unicode escape"),
+        ("ranges", "key = value"),
+    ] {
+        let err = CustomAlphabet::from_toml(toml).err().unwrap();
+        assert!(err.to_string().contains(problem), "{}: {}", toml, err);
+    }
+}
+
+#[test]
+fn scripts_cover_their_blocks() {
+    for script in Script::all() {
+        let info = script.info();
+        for digit in 0..script.len() {
+            let c = script.char_at(digit);
+            assert!(info.emits(c), "{} emitted {:?}", info.name, c);
+            assert_eq!(Some(digit), script.digit_of(c));
+        }
+        assert_eq!(None, script.digit_of('a'));
+    }
+    // The skin tone modifiers would merge with the emoji before them.
+    let emoji = Script::new(&SCRIPTS[3]);
+    assert_eq!(None, emoji.digit_of('\u{1F3FB}'));
+}
+
+#[test]
+fn scripts_declare_their_expansion() {
+    for script in Script::all() {
+        let info = script.info();
+        let width = script.char_at(0).len_utf8() as f32;
+        let radix = script.radix();
+        let expansion = width * radix.block_digits() as f32 / radix.block_bytes() as f32;
+        let Expansion::Fixed(declared) = info.expansion else {
+            panic!("{} has a variable expansion", info.name);
+        };
+        assert!((declared - expansion).abs() < 0.01, "{}", info.name);
+    }
+}
+
+fn encrypt<F: Fn(&[u8], &mut Vec<u8>) -> anyhow::Result<()>>(encrypt: F, bytes: &[u8]) -> String {
+    let mut encrypted = Vec::new();
+    encrypt(bytes, &mut encrypted).unwrap();
+    String::from_utf8(encrypted).unwrap()
+}
+
+#[test]
+fn published_encodings_match_their_references() {
+    // "hello world" is the example both encodings publish; the rest are worked through their
+    // repertoires: empty input, lone final bytes, exact fits and odd lengths.
+    let pairs: [(&[u8], &str, &str); 8] = [
+        (b"", "", ""),
+        (b"\x00", "\u{6BF}", "\u{1500}"),
+        (b"\xFF", "\u{A85F}", "\u{15FF}"),
+        (b"\x00\x00", "\u{4A0}\u{25F}", "\u{3400}"),
+        (b"\xFF\xFF", "\u{A85F}\u{29F}", "\u{285FF}"),
+        (b"abc", "\u{5711}\u{3F3F}", "\u{9761}\u{1563}"),
+        (b"hello world", "媒腻㐤┖ꈳ埳", "驨ꍬ啯𒁷ꍲᕤ"),
+        (
+            b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0A\x0B\x0C\x0D\x0E",
+            "\u{4A0}\u{66E0}\u{86E0}\u{76C0}\u{5EA0}\u{4A68}\u{3C58}\u{2CCE}",
+            "\u{3500}\u{3702}\u{3904}\u{3B06}\u{3D08}\u{3F0A}\u{410C}\u{150E}",
+        ),
+    ];
+    for (bytes, base32768, base65536) in pairs {
+        assert_eq!(base32768, encrypt(|b, w| Base32768.encrypt(b, w), bytes));
+        let mut decrypted = Vec::new();
+        Base32768
+            .decrypt(base32768.as_bytes(), &mut decrypted)
+            .unwrap();
+        assert_eq!(bytes, &decrypted[..]);
+
+        assert_eq!(base65536, encrypt(|b, w| Base65536.encrypt(b, w), bytes));
+        let mut decrypted = Vec::new();
+        Base65536
+            .decrypt(base65536.as_bytes(), &mut decrypted)
+            .unwrap();
+        assert_eq!(bytes, &decrypted[..]);
+    }
+}
+
+#[test]
+fn base65536_covers_its_repertoire() {
+    let mut seen = std::collections::HashSet::new();
+    for c0 in 0..=u8::MAX {
+        let pairs = (0..=u8::MAX).map(Some).chain([None]);
+        for pair in pairs.map(|c1| (c0, c1)) {
+            let encrypted = Base65536.encrypt_char_pair(pair);
+            assert!(Base65536::INFO.emits(encrypted), "emitted {:?}", encrypted);
+            assert_eq!(pair, Base65536.decrypt_char(encrypted).unwrap());
+            assert!(seen.insert(encrypted), "{:?} emitted twice", encrypted);
+        }
+    }
+    assert_eq!(Base65536::INFO.output_size() as usize, seen.len());
+}
+
+#[test]
+fn base32768_covers_its_repertoire() {
+    let mut seen = std::collections::HashSet::new();
+    for (bits, groups) in [(15, 0..1 << 15), (7, 0..1 << 7)] {
+        for group in groups {
+            let encrypted = Base32768.encrypt_group(group, bits);
+            assert!(Base32768::INFO.emits(encrypted), "emitted {:?}", encrypted);
+            assert_eq!((group, bits), Base32768.decrypt_char(encrypted).unwrap());
+            assert!(seen.insert(encrypted), "{:?} emitted twice", encrypted);
+        }
+    }
+    assert_eq!(Base32768::INFO.output_size() as usize, seen.len());
+}
+
+#[test]
+fn base32768_pads_with_ones() {
+    // One byte takes a whole 15 bit character, with 7 bits of padding.
+    let encrypted = encrypt(|b, w| Base32768.encrypt(b, w), &[0]);
+    assert_eq!(
+        vec![Base32768.encrypt_group(0x7F, 15)],
+        encrypted.chars().collect::<Vec<_>>()
+    );
+    // Two bytes leave a bit over, which goes in a 7 bit final character.
+    let encrypted = encrypt(|b, w| Base32768.encrypt(b, w), &[0xFF, 0xFF]);
+    let expected = [
+        Base32768.encrypt_group(0x7FFF, 15),
+        Base32768.encrypt_group(0x7F, 7),
+    ];
+    assert_eq!(&expected[..], &encrypted.chars().collect::<Vec<_>>()[..]);
+}
+
+#[test]
+fn published_encodings_round_trip() {
+    let bytes: Vec<u8> = (0..=255).rev().collect();
+    for len in 0..40 {
+        let encrypted = encrypt(|b, w| Base32768.encrypt(b, w), &bytes[..len]);
+        assert_eq!((8 * len).div_ceil(15), encrypted.chars().count());
+        let mut decrypted = Vec::new();
+        Base32768
+            .decrypt(encrypted.as_bytes(), &mut decrypted)
+            .unwrap();
+        assert_eq!(&bytes[..len], decrypted);
+
+        let encrypted = encrypt(|b, w| Base65536.encrypt(b, w), &bytes[..len]);
+        assert_eq!(len.div_ceil(2), encrypted.chars().count());
+        let mut decrypted = Vec::new();
+        Base65536
+            .decrypt(encrypted.as_bytes(), &mut decrypted)
+            .unwrap();
+        assert_eq!(&bytes[..len], decrypted);
+    }
+}
+
+#[test]
+fn published_encodings_reject_bad_input() {
+    let decrypt = |encrypted: &str| Base32768.decrypt(encrypted.as_bytes(), &mut Vec::new());
+    assert!(decrypt("a").is_err());
+    // Padding has to be 1 bits.
+    assert!(decrypt(&Base32768.encrypt_group(0, 15).to_string()).is_err());
+    // Nothing can follow the final character.
+    let final_char = Base32768.encrypt_group(0x7F, 7);
+    assert!(decrypt(&format!("{}{}", final_char, final_char)).is_err());
+
+    let decrypt = |encrypted: &str| Base65536.decrypt(encrypted.as_bytes(), &mut Vec::new());
+    assert!(decrypt("a").is_err());
+    assert!(decrypt("ᕤᕤ").is_err());
+}
+
+fn words(encrypted: &str) -> anyhow::Result<Vec<u8>> {
+    let mut decrypted = Vec::new();
+    Words.decrypt(encrypted.as_bytes(), &mut decrypted)?;
+    Ok(decrypted)
+}
+
+#[test]
+fn words_match_the_published_list() {
+    let bytes = [
+        0xE5, 0x82, 0x94, 0xF2, 0xE9, 0xA2, 0x27, 0x48, 0x6E, 0x8B, 0x06, 0x1B, 0x31, 0xCC, 0x52,
+        0x8F, 0xD7, 0xFA, 0x3F, 0x19,
+    ];
+    let expected = "topmost Istanbul Pluto vagabond treadmill Pacific brackish dictator goldfish \
+                    Medusa afflict bravado chatter revolver Dupont midsummer stopwatch whimsical \
+                    cowbell bottomless";
+    let mut encrypted = Vec::new();
+    Words.encrypt(&bytes[..], &mut encrypted).unwrap();
+    assert_eq!(expected, String::from_utf8(encrypted).unwrap());
+    assert_eq!(&bytes[..], words(expected).unwrap());
+}
+
+#[test]
+fn words_cover_every_byte() {
+    let bytes: Vec<u8> = (0..=255).chain((0..=255).rev()).collect();
+    let mut encrypted = Vec::new();
+    Words.encrypt(&bytes[..], &mut encrypted).unwrap();
+    let encrypted = String::from_utf8(encrypted).unwrap();
+    assert!(encrypted.chars().all(|c| Words::INFO.emits(c)));
+    assert_eq!(bytes, words(&encrypted).unwrap());
+}
+
+#[test]
+fn words_ignore_case_and_punctuation() {
+    assert_eq!(vec![0xE5, 0x82], words("TOPMOST, istanbul.").unwrap());
+    assert_eq!(
+        vec![0xE5, 0x82, 0x94],
+        words("\n  Topmost--Istanbul;\npluto!\n").unwrap()
+    );
+    assert_eq!(Vec::<u8>::new(), words(" ... ").unwrap());
+}
+
+#[test]
+fn words_catch_swaps() {
+    // Swapped, dropped and repeated words all land on the wrong list.
+    for encrypted in ["Istanbul topmost", "topmost Pluto", "topmost topmost"] {
+        let err = words(encrypted).unwrap_err();
+        assert!(
+            err.to_string().contains("swapped"),
+            "{}: {}",
+            encrypted,
+            err
+        );
+    }
+    assert!(words("topmost telephones").is_err());
+    assert!(words("aardvarkaardvark").is_err());
+}
@@ -0,0 +1,656 @@
+use crate::cipherv2::Chars;
+use crate::info::{CipherInfo, Describe, Expansion};
+use anyhow::{Error, Result};
+use std::collections::HashMap;
+use std::io::{BufReader, BufWriter, Read, Write};
+
+// No word in either list is longer than this, anything longer can be turned away early.
+const MAX_WORD: usize = 11;
+
+/**
+ * Words writes each byte as a word from the PGP word list, so ciphertext can be read out over
+ * the phone. Bytes at even offsets take a word from the two syllable list and bytes at odd
+ * offsets one from the three syllable list, so a word that's been dropped, repeated or
+ * swapped with its neighbour shows up as a word from the wrong list.
+ *
+ * Decryption ignores case, and reads anything that isn't a letter as a gap between words, so
+ * the words can come back capitalised, on separate lines or with punctuation between them.
+ */
+pub struct Words;
+
+impl Describe for Words {
+    const INFO: CipherInfo = CipherInfo {
+        name: "words",
+        id: 15,
+        description: "each byte as a word from the PGP word list",
+        expansion: Expansion::Variable {
+            min: 5.0,
+            max: 12.0,
+        },
+        output_ranges: &[0x20..=0x20, 0x41..=0x5A, 0x61..=0x7A],
+        binary_safe: true,
+    };
+}
+
+impl Words {
+    /// The word for the byte at `offset` in the message.
+    pub fn encrypt_byte(&self, byte: u8, offset: usize) -> &'static str {
+        if offset.is_multiple_of(2) {
+            EVEN[byte as usize]
+        } else {
+            ODD[byte as usize]
+        }
+    }
+
+    pub fn encrypt<R: Read, W: Write>(&self, reader: R, writer: W) -> Result<()> {
+        let mut writer = BufWriter::new(writer);
+        for (offset, byte) in BufReader::new(reader).bytes().enumerate() {
+            if offset > 0 {
+                writer.write_all(b" ")?;
+            }
+            writer.write_all(self.encrypt_byte(byte?, offset).as_bytes())?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn decrypt<R: Read, W: Write>(&self, reader: R, writer: W) -> Result<()> {
+        let mut writer = BufWriter::new(writer);
+        let mut decoder = Decoder::new();
+        for c in Chars::new(BufReader::new(reader)) {
+            if let Some(byte) = decoder.push(c?)? {
+                writer.write_all(&[byte])?;
+            }
+        }
+        if let Some(byte) = decoder.finish()? {
+            writer.write_all(&[byte])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Gathers letters into words and looks them up, keeping count of where in the message it is.
+struct Decoder {
+    // Every word, lower case, with its byte and whether it's from the odd list.
+    words: HashMap<String, (u8, bool)>,
+    word: String,
+    offset: usize,
+}
+
+impl Decoder {
+    fn new() -> Self {
+        let mut words = HashMap::with_capacity(512);
+        for (odd, list) in [(false, &EVEN), (true, &ODD)] {
+            for (byte, word) in list.iter().enumerate() {
+                words.insert(word.to_ascii_lowercase(), (byte as u8, odd));
+            }
+        }
+        Decoder {
+            words,
+            word: String::with_capacity(MAX_WORD),
+            offset: 0,
+        }
+    }
+
+    /// Takes the next char, and hands back a byte if it finished off a word.
+    fn push(&mut self, c: char) -> Result<Option<u8>> {
+        if !c.is_ascii_alphabetic() {
+            return self.finish();
+        }
+        if self.word.len() == MAX_WORD {
+            return Err(Error::msg(format!(
+                "'{}{}...' is too long to be in the word list",
+                self.word, c
+            )));
+        }
+        self.word.push(c.to_ascii_lowercase());
+        Ok(None)
+    }
+
+    /// Looks up the word gathered so far, if there is one.
+    fn finish(&mut self) -> Result<Option<u8>> {
+        if self.word.is_empty() {
+            return Ok(None);
+        }
+        let Some(&(byte, odd)) = self.words.get(&self.word) else {
+            return Err(Error::msg(format!(
+                "'{}' isn't in the word list",
+                self.word
+            )));
+        };
+        let offset = self.offset;
+        if odd != (offset % 2 == 1) {
+            return Err(Error::msg(format!(
+                "'{}' is word {} but comes from the {} list, \
+                 a word has been dropped, repeated or swapped",
+                self.word,
+                offset + 1,
+                if odd { "odd" } else { "even" }
+            )));
+        }
+        self.word.clear();
+        self.offset += 1;
+        Ok(Some(byte))
+    }
+}
+
+// The two syllable words, for bytes at even offsets.
+const EVEN: [&str; 256] = [
+    "aardvark",
+    "absurd",
+    "accrue",
+    "acme",
+    "adrift",
+    "adult",
+    "afflict",
+    "ahead",
+    "aimless",
+    "Algol",
+    "allow",
+    "alone",
+    "ammo",
+    "ancient",
+    "apple",
+    "artist",
+    "assume",
+    "Athens",
+    "atlas",
+    "Aztec",
+    "baboon",
+    "backfield",
+    "backward",
+    "banjo",
+    "beaming",
+    "bedlamp",
+    "beehive",
+    "beeswax",
+    "befriend",
+    "Belfast",
+    "berserk",
+    "billiard",
+    "bison",
+    "blackjack",
+    "blockade",
+    "blowtorch",
+    "bluebird",
+    "bombast",
+    "bookshelf",
+    "brackish",
+    "breadline",
+    "breakup",
+    "brickyard",
+    "briefcase",
+    "Burbank",
+    "button",
+    "buzzard",
+    "cement",
+    "chairlift",
+    "chatter",
+    "checkup",
+    "chisel",
+    "choking",
+    "chopper",
+    "Christmas",
+    "clamshell",
+    "classic",
+    "classroom",
+    "cleanup",
+    "clockwork",
+    "cobra",
+    "commence",
+    "concert",
+    "cowbell",
+    "crackdown",
+    "cranky",
+    "crowfoot",
+    "crucial",
+    "crumpled",
+    "crusade",
+    "cubic",
+    "dashboard",
+    "deadbolt",
+    "deckhand",
+    "dogsled",
+    "dragnet",
+    "drainage",
+    "dreadful",
+    "drifter",
+    "dropper",
+    "drumbeat",
+    "drunken",
+    "Dupont",
+    "dwelling",
+    "eating",
+    "edict",
+    "egghead",
+    "eightball",
+    "endorse",
+    "endow",
+    "enlist",
+    "erase",
+    "escape",
+    "exceed",
+    "eyeglass",
+    "eyetooth",
+    "facial",
+    "fallout",
+    "flagpole",
+    "flatfoot",
+    "flytrap",
+    "fracture",
+    "framework",
+    "freedom",
+    "frighten",
+    "gazelle",
+    "Geiger",
+    "glitter",
+    "glucose",
+    "goggles",
+    "goldfish",
+    "gremlin",
+    "guidance",
+    "hamlet",
+    "highchair",
+    "hockey",
+    "indoors",
+    "indulge",
+    "inverse",
+    "involve",
+    "island",
+    "jawbone",
+    "keyboard",
+    "kickoff",
+    "kiwi",
+    "klaxon",
+    "locale",
+    "lockup",
+    "merit",
+    "minnow",
+    "miser",
+    "Mohawk",
+    "mural",
+    "music",
+    "necklace",
+    "Neptune",
+    "newborn",
+    "nightbird",
+    "Oakland",
+    "obtuse",
+    "offload",
+    "optic",
+    "orca",
+    "payday",
+    "peachy",
+    "pheasant",
+    "physique",
+    "playhouse",
+    "Pluto",
+    "preclude",
+    "prefer",
+    "preshrunk",
+    "printer",
+    "prowler",
+    "pupil",
+    "puppy",
+    "python",
+    "quadrant",
+    "quiver",
+    "quota",
+    "ragtime",
+    "ratchet",
+    "rebirth",
+    "reform",
+    "regain",
+    "reindeer",
+    "rematch",
+    "repay",
+    "retouch",
+    "revenge",
+    "reward",
+    "rhythm",
+    "ribcage",
+    "ringbolt",
+    "robust",
+    "rocker",
+    "ruffled",
+    "sailboat",
+    "sawdust",
+    "scallion",
+    "scenic",
+    "scorecard",
+    "Scotland",
+    "seabird",
+    "select",
+    "sentence",
+    "shadow",
+    "shamrock",
+    "showgirl",
+    "skullcap",
+    "skydive",
+    "slingshot",
+    "slowdown",
+    "snapline",
+    "snapshot",
+    "snowcap",
+    "snowslide",
+    "solo",
+    "southward",
+    "soybean",
+    "spaniel",
+    "spearhead",
+    "spellbind",
+    "spheroid",
+    "spigot",
+    "spindle",
+    "spyglass",
+    "stagehand",
+    "stagnate",
+    "stairway",
+    "standard",
+    "stapler",
+    "steamship",
+    "sterling",
+    "stockman",
+    "stopwatch",
+    "stormy",
+    "sugar",
+    "surmount",
+    "suspense",
+    "sweatband",
+    "swelter",
+    "tactics",
+    "talon",
+    "tapeworm",
+    "tempest",
+    "tiger",
+    "tissue",
+    "tonic",
+    "topmost",
+    "tracker",
+    "transit",
+    "trauma",
+    "treadmill",
+    "Trojan",
+    "trouble",
+    "tumor",
+    "tunnel",
+    "tycoon",
+    "uncut",
+    "unearth",
+    "unwind",
+    "uproot",
+    "upset",
+    "upshot",
+    "vapor",
+    "village",
+    "virus",
+    "Vulcan",
+    "waffle",
+    "wallet",
+    "watchword",
+    "wayside",
+    "willow",
+    "woodlark",
+    "Zulu",
+];
+
+// The three syllable words, for bytes at odd offsets.
+const ODD: [&str; 256] = [
+    "adroitness",
+    "adviser",
+    "aftermath",
+    "aggregate",
+    "alkali",
+    "almighty",
+    "amulet",
+    "amusement",
+    "antenna",
+    "applicant",
+    "Apollo",
+    "armistice",
+    "article",
+    "asteroid",
+    "Atlantic",
+    "atmosphere",
+    "autopsy",
+    "Babylon",
+    "backwater",
+    "barbecue",
+    "belowground",
+    "bifocals",
+    "bodyguard",
+    "bookseller",
+    "borderline",
+    "bottomless",
+    "Bradbury",
+    "bravado",
+    "Brazilian",
+    "breakaway",
+    "Burlington",
+    "businessman",
+    "butterfat",
+    "Camelot",
+    "candidate",
+    "cannonball",
+    "Capricorn",
+    "caravan",
+    "caretaker",
+    "celebrate",
+    "cellulose",
+    "certify",
+    "chambermaid",
+    "Cherokee",
+    "Chicago",
+    "clergyman",
+    "coherence",
+    "combustion",
+    "commando",
+    "company",
+    "component",
+    "concurrent",
+    "confidence",
+    "conformist",
+    "congregate",
+    "consensus",
+    "consulting",
+    "corporate",
+    "corrosion",
+    "councilman",
+    "crossover",
+    "crucifix",
+    "cumbersome",
+    "customer",
+    "Dakota",
+    "decadence",
+    "December",
+    "decimal",
+    "designing",
+    "detector",
+    "detergent",
+    "determine",
+    "dictator",
+    "dinosaur",
+    "direction",
+    "disable",
+    "disbelief",
+    "disruptive",
+    "distortion",
+    "document",
+    "embezzle",
+    "enchanting",
+    "enrollment",
+    "enterprise",
+    "equation",
+    "equipment",
+    "escapade",
+    "Eskimo",
+    "everyday",
+    "examine",
+    "existence",
+    "exodus",
+    "fascinate",
+    "filament",
+    "finicky",
+    "forever",
+    "fortitude",
+    "frequency",
+    "gadgetry",
+    "Galveston",
+    "getaway",
+    "glossary",
+    "gossamer",
+    "graduate",
+    "gravity",
+    "guitarist",
+    "hamburger",
+    "Hamilton",
+    "handiwork",
+    "hazardous",
+    "headwaters",
+    "hemisphere",
+    "hesitate",
+    "hideaway",
+    "holiness",
+    "hurricane",
+    "hydraulic",
+    "impartial",
+    "impetus",
+    "inception",
+    "indigo",
+    "inertia",
+    "infancy",
+    "inferno",
+    "informant",
+    "insincere",
+    "insurgent",
+    "integrate",
+    "intention",
+    "inventive",
+    "Istanbul",
+    "Jamaica",
+    "Jupiter",
+    "leprosy",
+    "letterhead",
+    "liberty",
+    "maritime",
+    "matchmaker",
+    "maverick",
+    "Medusa",
+    "megaton",
+    "microscope",
+    "microwave",
+    "midsummer",
+    "millionaire",
+    "miracle",
+    "misnomer",
+    "molasses",
+    "molecule",
+    "Montana",
+    "monument",
+    "mosquito",
+    "narrative",
+    "nebula",
+    "newsletter",
+    "Norwegian",
+    "October",
+    "Ohio",
+    "onlooker",
+    "opulent",
+    "Orlando",
+    "outfielder",
+    "Pacific",
+    "pandemic",
+    "Pandora",
+    "paperweight",
+    "paragon",
+    "paragraph",
+    "paramount",
+    "passenger",
+    "pedigree",
+    "Pegasus",
+    "penetrate",
+    "perceptive",
+    "performance",
+    "pharmacy",
+    "phonetic",
+    "photograph",
+    "pioneer",
+    "pocketful",
+    "politeness",
+    "positive",
+    "potato",
+    "processor",
+    "provincial",
+    "proximate",
+    "puberty",
+    "publisher",
+    "pyramid",
+    "quantity",
+    "racketeer",
+    "rebellion",
+    "recipe",
+    "recover",
+    "repellent",
+    "replica",
+    "reproduce",
+    "resistor",
+    "responsive",
+    "retraction",
+    "retrieval",
+    "retrospect",
+    "revenue",
+    "revival",
+    "revolver",
+    "sandalwood",
+    "sardonic",
+    "Saturday",
+    "savagery",
+    "scavenger",
+    "sensation",
+    "sociable",
+    "souvenir",
+    "specialist",
+    "speculate",
+    "stethoscope",
+    "stupendous",
+    "supportive",
+    "surrender",
+    "suspicious",
+    "sympathy",
+    "tambourine",
+    "telephone",
+    "therapist",
+    "tobacco",
+    "tolerance",
+    "tomorrow",
+    "torpedo",
+    "tradition",
+    "travesty",
+    "trombonist",
+    "truncated",
+    "typewriter",
+    "ultimate",
+    "undaunted",
+    "underfoot",
+    "unicorn",
+    "unify",
+    "universe",
+    "unravel",
+    "upcoming",
+    "vacancy",
+    "vagabond",
+    "vertigo",
+    "Virginia",
+    "visitor",
+    "vocalist",
+    "voyager",
+    "warranty",
+    "Waterloo",
+    "whimsical",
+    "Wichita",
+    "Wilmington",
+    "Wyoming",
+    "yesteryear",
+    "Yucatan",
+];